@@ -0,0 +1,229 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    action::GameAction,
+    blocks::RotationDirection,
+    blocks_manager::BlocksManager,
+    board::{ActivePiece, Board, Grid, Shift},
+    constants::{COLUMNS, ROWS},
+};
+
+const SEARCH_ACTIONS: [GameAction; 6] = [
+    GameAction::MoveLeft,
+    GameAction::MoveRight,
+    GameAction::SoftDrop,
+    GameAction::RotateCW,
+    GameAction::RotateCCW,
+    GameAction::Rotate180,
+];
+
+const DANGER_ROWS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub aggregate_height: f32,
+    pub holes: f32,
+    pub bumpiness: f32,
+    pub lines: f32,
+    pub t_slots: f32,
+    pub score: f32,
+    pub danger: f32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            aggregate_height: -0.51,
+            holes: -0.36,
+            bumpiness: -0.18,
+            lines: 0.76,
+            t_slots: 0.4,
+            score: 0.004,
+            danger: -10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub hold: bool,
+    pub piece: ActivePiece,
+    pub actions: Vec<GameAction>,
+    pub evaluation: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ai {
+    pub weights: Weights,
+}
+
+impl Ai {
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    pub fn best_placement(
+        &self,
+        board: &Board,
+        blocks_manager: &BlocksManager,
+    ) -> Option<Placement> {
+        if board.is_paused() {
+            return None;
+        }
+
+        let mut best = self.best_for_active_piece(board, false);
+
+        if board.hold_state.can_hold {
+            let mut held_board = board.clone();
+            let mut preview = blocks_manager.clone();
+            if held_board.hold_block(&mut preview) {
+                if let Some(placement) = self.best_for_active_piece(&held_board, true) {
+                    if best
+                        .as_ref()
+                        .is_none_or(|b| placement.evaluation > b.evaluation)
+                    {
+                        best = Some(placement);
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    pub fn evaluate(&self, board: &Board, piece: ActivePiece) -> f32 {
+        let mut result = board.clone();
+        result.active_piece = Some(piece);
+
+        let lines_before = result.stats.cleaned_lines;
+        let score_before = result.stats.score;
+        result.lock_current_block();
+        let lines = result.stats.cleaned_lines - lines_before;
+        let score = result.stats.score - score_before;
+
+        let grid = result.grid();
+        let heights = column_heights(grid);
+        let aggregate_height: usize = heights.iter().sum();
+        let bumpiness: usize = heights.windows(2).map(|w| w[0].abs_diff(w[1])).sum();
+        let danger = grid[..DANGER_ROWS]
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_some())
+            .count();
+
+        let weights = &self.weights;
+        weights.aggregate_height * aggregate_height as f32
+            + weights.holes * count_holes(grid) as f32
+            + weights.bumpiness * bumpiness as f32
+            + weights.lines * lines as f32
+            + weights.t_slots * count_t_slots(grid) as f32
+            + weights.score * score as f32
+            + weights.danger * danger as f32
+    }
+
+    fn best_for_active_piece(&self, board: &Board, hold: bool) -> Option<Placement> {
+        reachable_placements(board)
+            .into_iter()
+            .map(|(piece, mut actions)| {
+                if hold {
+                    actions.insert(0, GameAction::Hold);
+                }
+                actions.push(GameAction::HardDrop);
+                Placement {
+                    hold,
+                    piece,
+                    actions,
+                    evaluation: self.evaluate(board, piece),
+                }
+            })
+            .max_by(|a, b| a.evaluation.total_cmp(&b.evaluation))
+    }
+}
+
+pub fn reachable_placements(board: &Board) -> Vec<(ActivePiece, Vec<GameAction>)> {
+    let Some(start) = board.active_piece else {
+        return Vec::new();
+    };
+
+    let mut sim = board.clone();
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, Vec::new())]);
+    let mut placements = Vec::new();
+
+    while let Some((piece, path)) = queue.pop_front() {
+        sim.active_piece = Some(piece);
+        if sim.is_grounded() {
+            placements.push((piece, path.clone()));
+        }
+
+        for action in SEARCH_ACTIONS {
+            sim.active_piece = Some(piece);
+            let moved = match action {
+                GameAction::MoveLeft => sim.move_block_x_axis(Shift::Left),
+                GameAction::MoveRight => sim.move_block_x_axis(Shift::Right),
+                GameAction::SoftDrop => sim.move_block_down(),
+                GameAction::RotateCW => sim.rotate_block(RotationDirection::Clockwise),
+                GameAction::RotateCCW => sim.rotate_block(RotationDirection::CounterClockwise),
+                GameAction::Rotate180 => sim.rotate_block(RotationDirection::Half),
+                _ => false,
+            };
+
+            let Some(next) = sim.active_piece.filter(|_| moved) else {
+                continue;
+            };
+
+            if visited.insert(next) {
+                let mut next_path = path.clone();
+                next_path.push(action);
+                queue.push_back((next, next_path));
+            }
+        }
+    }
+
+    placements
+}
+
+fn column_heights(grid: &Grid) -> [usize; COLUMNS as usize] {
+    let mut heights = [0; COLUMNS as usize];
+    for (x, height) in heights.iter_mut().enumerate() {
+        if let Some(y) = (0..ROWS as usize).find(|&y| grid[y][x].is_some()) {
+            *height = ROWS as usize - y;
+        }
+    }
+    heights
+}
+
+fn count_holes(grid: &Grid) -> usize {
+    (0..COLUMNS as usize)
+        .map(|x| {
+            (0..ROWS as usize)
+                .skip_while(|&y| grid[y][x].is_none())
+                .filter(|&y| grid[y][x].is_none())
+                .count()
+        })
+        .sum()
+}
+
+fn count_t_slots(grid: &Grid) -> usize {
+    let filled = |x: isize, y: isize| {
+        x < 0
+            || x >= COLUMNS as isize
+            || y >= ROWS as isize
+            || (y >= 0 && grid[y as usize][x as usize].is_some())
+    };
+
+    let mut slots = 0;
+    for y in 0..ROWS as isize - 1 {
+        for x in 1..COLUMNS as isize - 1 {
+            let is_open =
+                !filled(x - 1, y) && !filled(x, y) && !filled(x + 1, y) && !filled(x, y + 1);
+            let has_floor = filled(x - 1, y + 1) && filled(x + 1, y + 1) && filled(x, y + 2);
+            let overhangs = filled(x - 1, y - 1) as u8 + filled(x + 1, y - 1) as u8;
+
+            if is_open && has_floor && overhangs == 1 {
+                slots += 1;
+            }
+        }
+    }
+    slots
+}
@@ -1,10 +1,9 @@
-use ratatui::style::Color;
-
+use serde::{Deserialize, Serialize};
 use strum::{EnumCount, VariantArray};
 
-use crate::{board::Coords, colors::ORANGE};
+use crate::board::Coords;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum Rotation {
     #[default]
     Deg0,
@@ -13,7 +12,22 @@ pub enum Rotation {
     Deg270,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationDirection {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
 impl Rotation {
+    pub const fn rotate(self, direction: RotationDirection) -> Self {
+        match direction {
+            RotationDirection::Clockwise => self.rotate_clockwise(),
+            RotationDirection::CounterClockwise => self.rotate_counter_clockwise(),
+            RotationDirection::Half => self.rotate_180(),
+        }
+    }
+
     pub const fn rotate_clockwise(self) -> Self {
         match self {
             Self::Deg0 => Self::Deg90,
@@ -42,7 +56,38 @@ impl Rotation {
     }
 }
 
-#[derive(Debug, Clone, Copy, EnumCount, VariantArray, PartialEq, Eq)]
+pub type KickOffsets = &'static [(isize, isize)];
+
+// SRS kick data as published in the guideline, positive y points up.
+const JLSTZ_KICKS_0_R: [(isize, isize); 5] = [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)];
+const JLSTZ_KICKS_R_0: [(isize, isize); 5] = [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)];
+const JLSTZ_KICKS_R_2: [(isize, isize); 5] = [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)];
+const JLSTZ_KICKS_2_R: [(isize, isize); 5] = [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)];
+const JLSTZ_KICKS_2_L: [(isize, isize); 5] = [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)];
+const JLSTZ_KICKS_L_2: [(isize, isize); 5] = [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)];
+const JLSTZ_KICKS_L_0: [(isize, isize); 5] = [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)];
+const JLSTZ_KICKS_0_L: [(isize, isize); 5] = [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)];
+
+const I_KICKS_0_R: [(isize, isize); 5] = [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)];
+const I_KICKS_R_0: [(isize, isize); 5] = [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)];
+const I_KICKS_R_2: [(isize, isize); 5] = [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)];
+const I_KICKS_2_R: [(isize, isize); 5] = [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)];
+const I_KICKS_2_L: [(isize, isize); 5] = [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)];
+const I_KICKS_L_2: [(isize, isize); 5] = [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)];
+const I_KICKS_L_0: [(isize, isize); 5] = [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)];
+const I_KICKS_0_L: [(isize, isize); 5] = [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)];
+
+// The guideline has no 180 rotation, these are the kicks used by tetr.io.
+const KICKS_0_2: [(isize, isize); 6] = [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)];
+const KICKS_2_0: [(isize, isize); 6] = [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)];
+const KICKS_R_L: [(isize, isize); 6] = [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)];
+const KICKS_L_R: [(isize, isize); 6] = [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)];
+
+const NO_KICKS: [(isize, isize); 1] = [(0, 0)];
+
+#[derive(
+    Debug, Clone, Copy, EnumCount, VariantArray, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
 pub enum Block {
     Square,
     T,
@@ -62,15 +107,28 @@ impl Block {
         }
     }
 
-    pub const fn color(self) -> Color {
+    pub const fn letter(self) -> char {
         match self {
-            Self::Square => Color::Yellow,
-            Self::T => Color::Magenta,
-            Self::Line => Color::Cyan,
-            Self::L => ORANGE,
-            Self::J => Color::Blue,
-            Self::Z => Color::Red,
-            Self::S => Color::Green,
+            Self::Square => 'O',
+            Self::T => 'T',
+            Self::Line => 'I',
+            Self::L => 'L',
+            Self::J => 'J',
+            Self::Z => 'Z',
+            Self::S => 'S',
+        }
+    }
+
+    pub const fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'O' => Some(Self::Square),
+            'T' => Some(Self::T),
+            'I' => Some(Self::Line),
+            'L' => Some(Self::L),
+            'J' => Some(Self::J),
+            'Z' => Some(Self::Z),
+            'S' => Some(Self::S),
+            _ => None,
         }
     }
 
@@ -86,18 +144,54 @@ impl Block {
         }
     }
 
+    pub const fn kick_offsets(self, from: Rotation, to: Rotation) -> KickOffsets {
+        use Rotation::{Deg0, Deg180, Deg270, Deg90};
+
+        match (self, from, to) {
+            (Self::Square, _, _) => &NO_KICKS,
+            (_, Deg0, Deg180) => &KICKS_0_2,
+            (_, Deg180, Deg0) => &KICKS_2_0,
+            (_, Deg90, Deg270) => &KICKS_R_L,
+            (_, Deg270, Deg90) => &KICKS_L_R,
+            (Self::Line, Deg0, Deg90) => &I_KICKS_0_R,
+            (Self::Line, Deg90, Deg0) => &I_KICKS_R_0,
+            (Self::Line, Deg90, Deg180) => &I_KICKS_R_2,
+            (Self::Line, Deg180, Deg90) => &I_KICKS_2_R,
+            (Self::Line, Deg180, Deg270) => &I_KICKS_2_L,
+            (Self::Line, Deg270, Deg180) => &I_KICKS_L_2,
+            (Self::Line, Deg270, Deg0) => &I_KICKS_L_0,
+            (Self::Line, Deg0, Deg270) => &I_KICKS_0_L,
+            (_, Deg0, Deg90) => &JLSTZ_KICKS_0_R,
+            (_, Deg90, Deg0) => &JLSTZ_KICKS_R_0,
+            (_, Deg90, Deg180) => &JLSTZ_KICKS_R_2,
+            (_, Deg180, Deg90) => &JLSTZ_KICKS_2_R,
+            (_, Deg180, Deg270) => &JLSTZ_KICKS_2_L,
+            (_, Deg270, Deg180) => &JLSTZ_KICKS_L_2,
+            (_, Deg270, Deg0) => &JLSTZ_KICKS_L_0,
+            (_, Deg0, Deg270) => &JLSTZ_KICKS_0_L,
+            _ => &NO_KICKS,
+        }
+    }
+
+    pub const fn is_far_kick(self, from: Rotation, to: Rotation, kick: usize) -> bool {
+        let is_quarter_turn = !matches!(
+            (from, to),
+            (Rotation::Deg0, Rotation::Deg180)
+                | (Rotation::Deg180, Rotation::Deg0)
+                | (Rotation::Deg90, Rotation::Deg270)
+                | (Rotation::Deg270, Rotation::Deg90)
+        );
+        matches!(self, Self::T) && is_quarter_turn && kick == 4
+    }
+
     pub fn get_coordinates(self, rotation: Rotation) -> [Coords; 4] {
         let len = self.side_len();
-        let color = self.color();
-
-        self.base_coordinates().map(|(x, y)| {
-            let (rotated_x, rotated_y) = match rotation {
-                Rotation::Deg0 => (x, y),
-                Rotation::Deg90 => (len - 1 - y, x),
-                Rotation::Deg180 => (len - 1 - x, len - 1 - y),
-                Rotation::Deg270 => (y, len - 1 - x),
-            };
-            (rotated_x, rotated_y, color)
+
+        self.base_coordinates().map(|(x, y)| match rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (len - 1 - y, x),
+            Rotation::Deg180 => (len - 1 - x, len - 1 - y),
+            Rotation::Deg270 => (y, len - 1 - x),
         })
     }
 }
@@ -1,19 +1,74 @@
 use crate::blocks::Block;
 
 use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
 use strum::{EnumCount, VariantArray};
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "BlocksManagerState", into = "BlocksManagerState")]
 pub struct BlocksManager {
     bags: [[Block; Block::COUNT]; 2],
     active_bag: u8,
     current_index: u8,
     rng: SmallRng,
     seed: Option<u64>,
+    current_seed: u64,
+    shuffles: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlocksManagerState {
+    bags: [[Block; Block::COUNT]; 2],
+    active_bag: u8,
+    current_index: u8,
+    seed: Option<u64>,
+    current_seed: u64,
+    shuffles: u64,
+}
+
+impl From<BlocksManager> for BlocksManagerState {
+    fn from(manager: BlocksManager) -> Self {
+        Self {
+            bags: manager.bags,
+            active_bag: manager.active_bag,
+            current_index: manager.current_index,
+            seed: manager.seed,
+            current_seed: manager.current_seed,
+            shuffles: manager.shuffles,
+        }
+    }
+}
+
+impl From<BlocksManagerState> for BlocksManager {
+    fn from(state: BlocksManagerState) -> Self {
+        let mut rng = SmallRng::seed_from_u64(state.current_seed);
+        let mut scratch: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
+        for _ in 0..state.shuffles {
+            scratch.shuffle(&mut rng);
+        }
+
+        Self {
+            bags: state.bags,
+            active_bag: state.active_bag % 2,
+            current_index: state.current_index % Block::COUNT as u8,
+            rng,
+            seed: state.seed,
+            current_seed: state.current_seed,
+            shuffles: state.shuffles,
+        }
+    }
+}
+
+impl Default for BlocksManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BlocksManager {
     pub fn new() -> Self {
-        let mut rng = rand::make_rng();
+        let current_seed = rand::random();
+        let mut rng = SmallRng::seed_from_u64(current_seed);
         let mut bag_1: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
         let mut bag_2: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
 
@@ -26,10 +81,12 @@ impl BlocksManager {
             current_index: 0,
             rng,
             seed: None,
+            current_seed,
+            shuffles: 2,
         }
     }
 
-    #[cfg(any(debug_assertions, feature = "vhs"))]
+    #[cfg(any(test, debug_assertions, feature = "vhs"))]
     pub fn with_seed(seed: u64) -> Self {
         let mut rng = SmallRng::seed_from_u64(seed);
         let mut bag_1: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
@@ -44,21 +101,34 @@ impl BlocksManager {
             current_index: 0,
             rng,
             seed: Some(seed),
+            current_seed: seed,
+            shuffles: 2,
         }
     }
 
+    pub fn current_seed(&self) -> u64 {
+        self.current_seed
+    }
+
+    pub fn dealt(&self) -> u64 {
+        self.shuffles * Block::COUNT as u64 + self.current_index as u64
+    }
+
     pub fn reset(&mut self) {
-        if let Some(seed) = self.seed {
-            self.rng = SmallRng::seed_from_u64(seed);
-        } else {
-            self.rng = rand::make_rng();
-        }
+        let seed = self.seed.unwrap_or_else(rand::random);
+        self.reset_with_seed(seed);
+    }
+
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        self.current_seed = seed;
+        self.rng = SmallRng::seed_from_u64(seed);
 
         let mut bag_1: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
         let mut bag_2: [Block; Block::COUNT] = Block::VARIANTS.try_into().unwrap();
 
         bag_1.shuffle(&mut self.rng);
         bag_2.shuffle(&mut self.rng);
+        self.shuffles = 2;
 
         self.bags = [bag_1, bag_2];
         self.active_bag = 0;
@@ -102,6 +172,7 @@ impl BlocksManager {
 
         if self.current_index as usize == Block::COUNT {
             self.bags[self.active_bag as usize].shuffle(&mut self.rng);
+            self.shuffles += 1;
             self.active_bag = 1 - self.active_bag;
             self.current_index = 0;
         }
@@ -114,12 +185,12 @@ impl BlocksManager {
         let next = &self.bags[(1 - self.active_bag) as usize];
 
         let mut result = [Block::Square; 5];
-        for i in 0..5 {
+        for (i, slot) in result.iter_mut().enumerate() {
             let idx = self.current_index as usize + i;
             if idx < Block::COUNT {
-                result[i] = curr[idx];
+                *slot = curr[idx];
             } else {
-                result[i] = next[idx - Block::COUNT];
+                *slot = next[idx - Block::COUNT];
             }
         }
         result
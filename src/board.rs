@@ -1,37 +1,86 @@
 use crate::{
-    blocks::{Block, Rotation},
+    action::GameAction,
+    blocks::{Block, Rotation, RotationDirection},
     blocks_manager::BlocksManager,
+    clear::{ClearKind, Spin},
     constants::{
-        COLUMNS, COMBO_NOTIFICATION_DURATION, GOAL_MULTIPLIER, LOCK_DELAY_FRAMES_DURATION,
-        MAX_DELAY_FRAMES_LOCK_RESETS, MAX_FALL_SPEED_LEVEL, ROWS,
+        COLUMNS, COMBO_NOTIFICATION_DURATION, FRAME_DURATION, GARBAGE_DELAY_DURATION,
+        GOAL_MULTIPLIER, MARATHON_LEVEL_LINES, MASTER_LINES, MAX_DELAY_FRAMES_LOCK_RESETS,
+        MAX_FALL_SPEED_LEVEL, ROWS,
     },
-    utils::timer::Timer,
+    garbage::{GarbageGenerator, RisingGarbage},
+    timer::{frames_to_duration, Timer},
+    timing::Timing,
 };
-use crossterm::event::KeyCode;
-use ratatui::{
-    buffer::Buffer,
-    layout::Rect,
-    style::{Color, Style},
-    widgets::Widget,
-};
-use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub type Coords = (u16, u16);
+pub type Grid = [[Option<Cell>; COLUMNS as usize]; ROWS as usize];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cell {
+    Block(Block),
+    Garbage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+    Left,
+    Right,
+}
 
-pub type Coords = (u16, u16, Color);
-pub type Grid = [[Option<Color>; COLUMNS as usize]; ROWS as usize];
+impl Shift {
+    pub const fn dx(self) -> isize {
+        match self {
+            Self::Left => -1,
+            Self::Right => 1,
+        }
+    }
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    Playing,
+    Paused,
+    ToppedOut,
+    GoalReached,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PlayState {
     #[default]
     Playing,
     Paused,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameGoal {
+    #[default]
+    None,
+    Lines(usize),
+    Time(Duration),
+    Dig {
+        rows: usize,
+        messiness: u32,
+    },
+    Survival {
+        accelerating: bool,
+    },
+    Marathon {
+        lines: usize,
+        start_level: usize,
+    },
+    Master,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ActivePiece {
     pub block: Block,
     pub rotation: Rotation,
     pub coord: (isize, isize),
     pub last_action_was_rotation: bool,
+    pub last_rotation_was_far_kick: bool,
 }
 
 impl ActivePiece {
@@ -41,56 +90,93 @@ impl ActivePiece {
             rotation: Rotation::Deg0,
             coord,
             last_action_was_rotation: false,
+            last_rotation_was_far_kick: false,
         }
     }
+
+    pub fn cells(&self) -> [(isize, isize); 4] {
+        let (x, y) = self.coord;
+        self.block
+            .get_coordinates(self.rotation)
+            .map(|(block_x, block_y)| (x + block_x as isize, y + block_y as isize))
+    }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct HoldState {
     pub block: Option<Block>,
     pub can_hold: bool,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct LockDelay {
-    pub timer: Option<Instant>,
+    pub started_at: Option<u64>,
     pub resets: usize,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SpawnDelay {
+    pub started_at: u64,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct LastMovement {
-    pub name: &'static str,
-    pub timer: Option<Instant>,
-    pub b2b_count: usize,
+    pub clear: ClearKind,
+    pub started_at: Option<u64>,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Combo {
     pub count: usize,
-    pub timer: Option<Instant>,
+    pub started_at: Option<u64>,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub struct GameStats {
     pub score: usize,
     pub cleaned_lines: usize,
     pub level: usize,
     pub fall_speed: Duration,
     pub b2b_count: usize,
+    #[serde(default)]
+    pub garbage_cleared: usize,
+    #[serde(default)]
+    pub start_level: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PendingGarbage {
+    pub lines: usize,
+    pub received_at: u64,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Board {
     pub play_state: PlayState,
+    pub goal: GameGoal,
     pub stats: GameStats,
     pub timer: Timer,
     pub active_piece: Option<ActivePiece>,
     pub hold_state: HoldState,
     pub lock_delay: LockDelay,
+    #[serde(default)]
+    pub spawn_delay: SpawnDelay,
     pub last_movement_state: LastMovement,
     pub combo: Combo,
+    #[serde(default)]
+    pub last_clear: Option<ClearKind>,
+    #[serde(default)]
+    pub outgoing_garbage: usize,
+    #[serde(default)]
+    pub pending_garbage: Vec<PendingGarbage>,
+    #[serde(default)]
+    released_garbage: Vec<usize>,
+    #[serde(default)]
+    rising_garbage: Option<RisingGarbage>,
 
     board: Grid,
+    gravity_acc: Duration,
 }
 
 impl Board {
@@ -110,12 +196,15 @@ impl Board {
 
     pub fn new_game(&mut self) {
         self.play_state = PlayState::Playing;
+        self.goal = GameGoal::None;
         self.stats = GameStats {
             level: 1,
             fall_speed: Duration::ZERO,
             score: 0,
             cleaned_lines: 0,
             b2b_count: 0,
+            garbage_cleared: 0,
+            start_level: 1,
         };
 
         self.board.iter_mut().for_each(|row| row.fill(None));
@@ -126,12 +215,228 @@ impl Board {
         };
         self.last_movement_state = LastMovement::default();
         self.combo = Combo::default();
+        self.last_clear = None;
+        self.outgoing_garbage = 0;
+        self.pending_garbage.clear();
+        self.released_garbage.clear();
+        self.rising_garbage = None;
         self.lock_delay = LockDelay::default();
+        self.spawn_delay = SpawnDelay::default();
+        self.gravity_acc = Duration::ZERO;
 
         self.timer.reset();
         self.timer.start();
     }
 
+    pub fn tick(&mut self, blocks_manager: &mut BlocksManager) -> TickOutcome {
+        if self.is_paused() {
+            return TickOutcome::Paused;
+        }
+
+        if !self.rise_garbage() {
+            self.timer.pause();
+            return TickOutcome::ToppedOut;
+        }
+
+        self.timer.tick();
+        self.gravity_acc += FRAME_DURATION;
+
+        if self.is_goal_reached() {
+            self.timer.pause();
+            return TickOutcome::GoalReached;
+        }
+
+        if !self.is_block_falling() {
+            if self.elapsed_since(self.spawn_delay.started_at) < self.spawn_delay.duration {
+                return TickOutcome::Playing;
+            }
+
+            let block = blocks_manager.get_next_block();
+            if !self.spawn_next_block(&block) {
+                self.timer.pause();
+                return TickOutcome::ToppedOut;
+            };
+        }
+
+        if self.timing().is_instant() {
+            while self.move_block_down() {}
+            self.gravity_acc = Duration::ZERO;
+        } else if self.stats.fall_speed > Duration::ZERO {
+            while self.gravity_acc >= self.stats.fall_speed {
+                self.gravity_acc -= self.stats.fall_speed;
+                let _ = self.move_block_down_or_set();
+            }
+        }
+
+        if self.stats.level > 0 {
+            self.check_lock_delay();
+        }
+
+        TickOutcome::Playing
+    }
+
+    pub fn apply_action(&mut self, action: GameAction, blocks_manager: &mut BlocksManager) -> bool {
+        if self.is_paused() && action != GameAction::Pause {
+            return false;
+        }
+
+        match action {
+            GameAction::MoveLeft => self.move_block_x_axis(Shift::Left),
+            GameAction::MoveRight => self.move_block_x_axis(Shift::Right),
+            GameAction::SoftDrop => self.soft_drop(),
+            GameAction::HardDrop => {
+                if !self.is_block_falling() {
+                    return false;
+                }
+                self.hard_drop();
+                true
+            }
+            GameAction::RotateCW => self.rotate_block(RotationDirection::Clockwise),
+            GameAction::RotateCCW => self.rotate_block(RotationDirection::CounterClockwise),
+            GameAction::Rotate180 => self.rotate_block(RotationDirection::Half),
+            GameAction::Hold => self.hold_block(blocks_manager),
+            GameAction::Pause => {
+                self.pause();
+                true
+            }
+        }
+    }
+
+    pub fn hard_drop(&mut self) {
+        while self.move_block_down() {}
+        self.lock_current_block();
+        self.gravity_acc = Duration::ZERO;
+    }
+
+    pub fn soft_drop(&mut self) -> bool {
+        if self.stats.level == 0 {
+            self.move_block_down()
+        } else {
+            self.move_block_down_or_set()
+        }
+    }
+
+    pub fn new_game_with_goal(&mut self, goal: GameGoal) {
+        self.new_game();
+        self.goal = goal;
+        if let GameGoal::Marathon { start_level, .. } = goal {
+            self.stats.level = start_level;
+            self.stats.start_level = start_level;
+        }
+    }
+
+    pub fn setup_goal_garbage(&mut self, seed: u64) {
+        match self.goal {
+            GameGoal::Dig { rows, messiness } => {
+                let mut garbage = GarbageGenerator::new(seed, messiness);
+                for _ in 0..rows {
+                    self.add_garbage(1, garbage.next_hole());
+                }
+            }
+            GameGoal::Survival { accelerating } => {
+                self.rising_garbage = Some(RisingGarbage::new(seed, accelerating));
+            }
+            _ => (),
+        }
+    }
+
+    fn rise_garbage(&mut self) -> bool {
+        let frame = self.timer.frames();
+        match self
+            .rising_garbage
+            .as_mut()
+            .and_then(|rise| rise.next_hole(frame))
+        {
+            Some(hole) => self.add_garbage(1, hole),
+            None => true,
+        }
+    }
+
+    pub fn take_outgoing_garbage(&mut self) -> usize {
+        std::mem::take(&mut self.outgoing_garbage)
+    }
+
+    pub fn queue_garbage(&mut self, lines: usize) {
+        if lines > 0 {
+            self.pending_garbage.push(PendingGarbage {
+                lines,
+                received_at: self.timer.frames(),
+            });
+        }
+    }
+
+    pub fn pending_garbage_lines(&self) -> usize {
+        self.pending_garbage
+            .iter()
+            .map(|garbage| garbage.lines)
+            .sum()
+    }
+
+    pub fn until_garbage_rise(&self) -> Option<Duration> {
+        self.rising_garbage
+            .as_ref()
+            .map(|rise| frames_to_duration(rise.next_rise().saturating_sub(self.timer.frames())))
+    }
+
+    pub fn is_garbage_ready(&self, garbage: &PendingGarbage) -> bool {
+        self.elapsed_since(garbage.received_at) >= GARBAGE_DELAY_DURATION
+    }
+
+    pub fn take_released_garbage(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.released_garbage)
+    }
+
+    fn cancel_garbage(&mut self, mut attack: usize) -> usize {
+        while attack > 0 {
+            let Some(garbage) = self.pending_garbage.first_mut() else {
+                break;
+            };
+            let cancelled = attack.min(garbage.lines);
+            garbage.lines -= cancelled;
+            attack -= cancelled;
+            if garbage.lines == 0 {
+                self.pending_garbage.remove(0);
+            }
+        }
+        attack
+    }
+
+    fn release_ready_garbage(&mut self) {
+        let ready = self
+            .pending_garbage
+            .iter()
+            .take_while(|garbage| self.is_garbage_ready(garbage))
+            .count();
+        self.released_garbage.extend(
+            self.pending_garbage
+                .drain(..ready)
+                .map(|garbage| garbage.lines),
+        );
+    }
+
+    pub fn add_garbage(&mut self, rows: usize, hole: usize) -> bool {
+        let rows = rows.min(ROWS as usize);
+        let mut topped_out = self.board[..rows]
+            .iter()
+            .any(|row| row.iter().any(Option::is_some));
+
+        self.board.copy_within(rows.., 0);
+        for row in &mut self.board[ROWS as usize - rows..] {
+            *row = [Some(Cell::Garbage); COLUMNS as usize];
+            row[hole.min(COLUMNS as usize - 1)] = None;
+        }
+
+        if let Some(mut piece) = self.active_piece {
+            while !self.can_place(piece.block, piece.coord, piece.rotation) && piece.coord.1 > 0 {
+                piece.coord.1 -= 1;
+            }
+            topped_out |= !self.can_place(piece.block, piece.coord, piece.rotation);
+            self.active_piece = Some(piece);
+        }
+
+        !topped_out
+    }
+
     pub fn new_with_grid(&mut self, grid: Grid) {
         self.new_game();
         self.board = grid;
@@ -144,10 +449,26 @@ impl Board {
         self.update_fall_speed();
     }
 
+    pub fn grid(&self) -> &Grid {
+        &self.board
+    }
+
     pub fn is_paused(&self) -> bool {
         self.play_state == PlayState::Paused
     }
 
+    pub fn is_goal_reached(&self) -> bool {
+        match self.goal {
+            GameGoal::None | GameGoal::Survival { .. } => false,
+            GameGoal::Lines(lines) | GameGoal::Marathon { lines, .. } => {
+                self.stats.cleaned_lines >= lines
+            }
+            GameGoal::Master => self.stats.cleaned_lines >= MASTER_LINES,
+            GameGoal::Time(duration) => self.timer.elapsed() >= duration,
+            GameGoal::Dig { rows, .. } => self.stats.garbage_cleared >= rows,
+        }
+    }
+
     pub fn is_block_falling(&self) -> bool {
         self.active_piece.is_some()
     }
@@ -204,39 +525,28 @@ impl Board {
         true
     }
 
-    pub fn rotate_block(&mut self, key: KeyCode) -> bool {
+    pub fn rotate_block(&mut self, direction: RotationDirection) -> bool {
         let Some(ref mut piece) = self.active_piece else {
             return false;
         };
 
-        let next_rotation = match key {
-            KeyCode::Char('z') | KeyCode::Char('Z') => piece.rotation.rotate_counter_clockwise(),
-            KeyCode::Char('a') | KeyCode::Char('A') => piece.rotation.rotate_180(),
-            _ => piece.rotation.rotate_clockwise(),
-        };
+        let next_rotation = piece.rotation.rotate(direction);
 
         let (x, y) = piece.coord;
-        const KICK_OFFSETS: [(isize, isize); 10] = [
-            (0, 0),
-            (1, 0),
-            (-1, 0),
-            (2, 0),
-            (-2, 0),
-            (0, -1),
-            (1, -1),
-            (-1, -1),
-            (0, -2),
-            (0, 1),
-        ];
-
         let block = piece.block;
-        for (dx, dy) in KICK_OFFSETS {
-            let test_coord = (x + dx, y + dy);
+        let rotation = piece.rotation;
+        for (kick, &(dx, dy)) in block
+            .kick_offsets(rotation, next_rotation)
+            .iter()
+            .enumerate()
+        {
+            let test_coord = (x + dx, y - dy);
             if self.can_place(block, test_coord, next_rotation) {
                 if let Some(ref mut p) = self.active_piece {
                     p.coord = test_coord;
                     p.rotation = next_rotation;
                     p.last_action_was_rotation = true;
+                    p.last_rotation_was_far_kick = block.is_far_kick(rotation, next_rotation, kick);
                 }
                 self.update_lock_delay_on_move();
                 return true;
@@ -263,17 +573,13 @@ impl Board {
         true
     }
 
-    pub fn move_block_x_axis(&mut self, key: KeyCode) {
+    pub fn move_block_x_axis(&mut self, shift: Shift) -> bool {
         let Some(ref mut piece) = self.active_piece else {
-            return;
+            return false;
         };
 
         let (x, y) = piece.coord;
-        let next_x = match key {
-            KeyCode::Left => x - 1,
-            KeyCode::Right => x + 1,
-            _ => return,
-        };
+        let next_x = x + shift.dx();
 
         let block = piece.block;
         let rotation = piece.rotation;
@@ -283,6 +589,9 @@ impl Board {
                 p.last_action_was_rotation = false;
             }
             self.update_lock_delay_on_move();
+            true
+        } else {
+            false
         }
     }
 
@@ -297,46 +606,81 @@ impl Board {
     fn update_lock_delay_on_move(&mut self) {
         if self.is_grounded() {
             if self.lock_delay.resets < MAX_DELAY_FRAMES_LOCK_RESETS {
-                self.lock_delay.timer = Some(Instant::now());
+                self.lock_delay.started_at = Some(self.timer.frames());
                 self.lock_delay.resets += 1;
             }
         } else {
-            self.lock_delay.timer = None;
+            self.lock_delay.started_at = None;
         }
     }
 
-    pub fn detect_t_spin(&self) -> bool {
-        let Some(piece) = self.active_piece else {
-            return false;
-        };
-        if piece.block != Block::T || !piece.last_action_was_rotation {
-            return false;
+    pub fn detect_spin(&self) -> Option<Spin> {
+        self.spin_of(&self.active_piece?)
+    }
+
+    pub fn spin_of(&self, piece: &ActivePiece) -> Option<Spin> {
+        if !piece.last_action_was_rotation {
+            return None;
+        }
+
+        match piece.block {
+            Block::T => self.detect_t_spin(piece),
+            Block::Square => None,
+            block => self
+                .is_immobile(piece)
+                .then_some(Spin { block, mini: false }),
         }
+    }
+
+    fn is_immobile(&self, piece: &ActivePiece) -> bool {
+        let (x, y) = piece.coord;
+        [(x - 1, y), (x + 1, y), (x, y - 1)]
+            .into_iter()
+            .all(|coord| !self.can_place(piece.block, coord, piece.rotation))
+    }
+
+    fn is_corner_occupied(&self, (cx, cy): (isize, isize)) -> bool {
+        cx < 0
+            || cx >= COLUMNS as isize
+            || cy < 0
+            || cy >= ROWS as isize
+            || self.board[cy as usize][cx as usize].is_some()
+    }
 
+    fn detect_t_spin(&self, piece: &ActivePiece) -> Option<Spin> {
         let (x, y) = piece.coord;
         let center_x = x + 1;
         let center_y = y + 1;
 
-        let corners = [
-            (center_x - 1, center_y - 1),
-            (center_x + 1, center_y - 1),
-            (center_x - 1, center_y + 1),
-            (center_x + 1, center_y + 1),
-        ];
+        let top_left = (center_x - 1, center_y - 1);
+        let top_right = (center_x + 1, center_y - 1);
+        let bottom_left = (center_x - 1, center_y + 1);
+        let bottom_right = (center_x + 1, center_y + 1);
 
-        let mut occupied_corners = 0;
-        for (cx, cy) in corners {
-            if cx < 0
-                || cx >= COLUMNS as isize
-                || cy < 0
-                || cy >= ROWS as isize
-                || (cy >= 0 && self.board[cy as usize][cx as usize].is_some())
-            {
-                occupied_corners += 1;
-            }
+        let (front, back) = match piece.rotation {
+            Rotation::Deg0 => ([top_left, top_right], [bottom_left, bottom_right]),
+            Rotation::Deg90 => ([top_right, bottom_right], [top_left, bottom_left]),
+            Rotation::Deg180 => ([bottom_left, bottom_right], [top_left, top_right]),
+            Rotation::Deg270 => ([top_left, bottom_left], [top_right, bottom_right]),
+        };
+
+        let front_occupied = front
+            .into_iter()
+            .filter(|&corner| self.is_corner_occupied(corner))
+            .count();
+        let back_occupied = back
+            .into_iter()
+            .filter(|&corner| self.is_corner_occupied(corner))
+            .count();
+
+        if front_occupied + back_occupied < 3 {
+            return None;
         }
 
-        occupied_corners >= 3
+        Some(Spin {
+            block: Block::T,
+            mini: front_occupied < 2 && !piece.last_rotation_was_far_kick,
+        })
     }
 
     pub fn is_board_empty(&self) -> bool {
@@ -347,34 +691,53 @@ impl Board {
         let Some(piece) = self.active_piece else {
             return;
         };
-        let is_t_spin = self.detect_t_spin();
+        let spin = self.detect_spin();
         let (x, y) = piece.coord;
-        for (block_x, block_y, color) in piece.block.get_coordinates(piece.rotation) {
+        for (block_x, block_y) in piece.block.get_coordinates(piece.rotation) {
             let board_x = x + block_x as isize;
             let board_y = y + block_y as isize;
             if board_x >= 0 && board_x < COLUMNS as isize && board_y >= 0 && board_y < ROWS as isize
             {
-                self.board[board_y as usize][board_x as usize] = Some(color);
+                self.board[board_y as usize][board_x as usize] = Some(Cell::Block(piece.block));
             }
         }
         self.active_piece = None;
         self.lock_delay = LockDelay::default();
-        self.clear_lines(is_t_spin);
+        let clear = self.clear_lines(spin);
+        self.outgoing_garbage += self.cancel_garbage(clear.attack());
+        if clear.lines == 0 {
+            self.release_ready_garbage();
+        }
+
+        let timing = self.timing();
+        self.spawn_delay = SpawnDelay {
+            started_at: self.timer.frames(),
+            duration: if clear.lines > 0 {
+                timing.spawn_delay + timing.line_clear_delay
+            } else {
+                timing.spawn_delay
+            },
+        };
+        self.last_clear = Some(clear);
+
+        if self.is_goal_reached() {
+            self.timer.pause();
+        }
     }
 
     pub fn check_lock_delay(&mut self) {
         if self.is_grounded() {
-            if let Some(timer) = self.lock_delay.timer {
-                if timer.elapsed() >= LOCK_DELAY_FRAMES_DURATION
+            if let Some(started_at) = self.lock_delay.started_at {
+                if self.elapsed_since(started_at) >= self.timing().lock_delay
                     || self.lock_delay.resets >= MAX_DELAY_FRAMES_LOCK_RESETS
                 {
                     self.lock_current_block();
                 }
             } else {
-                self.lock_delay.timer = Some(Instant::now());
+                self.lock_delay.started_at = Some(self.timer.frames());
             }
         } else {
-            self.lock_delay.timer = None;
+            self.lock_delay.started_at = None;
         }
     }
 
@@ -411,11 +774,11 @@ impl Board {
                 p.last_action_was_rotation = false;
             }
             if self.is_grounded() {
-                if self.lock_delay.timer.is_none() {
-                    self.lock_delay.timer = Some(Instant::now());
+                if self.lock_delay.started_at.is_none() {
+                    self.lock_delay.started_at = Some(self.timer.frames());
                 }
             } else {
-                self.lock_delay.timer = None;
+                self.lock_delay.started_at = None;
             }
             true
         } else {
@@ -429,7 +792,7 @@ impl Board {
         block
             .get_coordinates(rotation)
             .into_iter()
-            .all(|(block_x, block_y, _)| {
+            .all(|(block_x, block_y)| {
                 let board_x = square_x + block_x as isize;
                 let board_y = square_y + block_y as isize;
 
@@ -441,12 +804,16 @@ impl Board {
             })
     }
 
-    fn clear_lines(&mut self, is_t_spin: bool) {
+    fn clear_lines(&mut self, spin: Option<Spin>) -> ClearKind {
         let mut cleared = 0;
+        let mut garbage_cleared = 0;
 
         for y in (0..ROWS as usize).rev() {
             if self.board[y].iter().all(Option::is_some) {
                 cleared += 1;
+                if self.board[y].contains(&Some(Cell::Garbage)) {
+                    garbage_cleared += 1;
+                }
             } else if cleared > 0 {
                 self.board[y + cleared] = self.board[y];
             }
@@ -456,131 +823,57 @@ impl Board {
             self.board[y] = [None; COLUMNS as usize];
         }
 
-        let is_perfect_clear = cleared > 0 && self.is_board_empty();
-        let is_difficult = cleared == 4 || (is_t_spin && cleared > 0);
-
-        let mut base_score = 0;
-        let mut movement_name: &'static str = "";
-
-        if is_t_spin {
-            match cleared {
-                0 => {
-                    base_score = 400;
-                    movement_name = "T-Spin";
-                }
-                1 => {
-                    base_score = 800;
-                    movement_name = if self.stats.b2b_count > 0 {
-                        "B2B T-Spin Single"
-                    } else {
-                        "T-Spin Single"
-                    };
-                }
-                2 => {
-                    base_score = 1200;
-                    movement_name = if self.stats.b2b_count > 0 {
-                        "B2B T-Spin Double"
-                    } else {
-                        "T-Spin Double"
-                    };
-                }
-                3 => {
-                    base_score = 1600;
-                    movement_name = if self.stats.b2b_count > 0 {
-                        "B2B T-Spin Triple"
-                    } else {
-                        "T-Spin Triple"
-                    };
-                }
-                _ => {}
-            }
-        } else {
-            match cleared {
-                1 => {
-                    base_score = 100;
-                    movement_name = "single";
-                }
-                2 => {
-                    base_score = 300;
-                    movement_name = "double";
-                }
-                3 => {
-                    base_score = 500;
-                    movement_name = "triple";
-                }
-                4 => {
-                    base_score = 800;
-                    movement_name = if self.stats.b2b_count > 0 {
-                        "B2B quad"
-                    } else {
-                        "quad"
-                    };
-                }
-                _ => {}
-            }
-        }
+        let mut clear = ClearKind {
+            spin,
+            lines: cleared,
+            perfect_clear: cleared > 0 && self.is_board_empty(),
+            ..Default::default()
+        };
 
-        if is_difficult {
+        if clear.is_difficult() {
             if self.stats.b2b_count > 0 {
-                base_score = (base_score as f64 * 1.5) as usize;
+                clear.b2b = self.stats.b2b_count + 1;
             }
             self.stats.b2b_count += 1;
         } else if cleared > 0 {
             self.stats.b2b_count = 0;
         }
 
-        if is_perfect_clear {
-            let pc_bonus = match cleared {
-                1 => 800,
-                2 => 1200,
-                3 => 1800,
-                4 => 2000,
-                _ => 2000,
-            };
-            base_score += pc_bonus;
-            movement_name = "Perfect Clear!";
-        }
-
-        if !movement_name.is_empty() {
-            self.last_movement_state = LastMovement {
-                name: movement_name,
-                timer: Some(Instant::now()),
-                b2b_count: self.stats.b2b_count,
-            };
-        }
-
         if cleared > 0 {
-            if self.combo.count > 0 {
-                let combo_bonus = 50 * self.combo.count * self.stats.level;
-                self.stats.score += combo_bonus;
-            }
+            clear.combo = self.combo.count;
             self.combo.count += 1;
-            self.combo.timer = Some(Instant::now());
+            self.combo.started_at = Some(self.timer.frames());
         } else {
             self.combo.count = 0;
         }
 
-        self.stats.score += base_score * self.stats.level;
+        if clear.is_notable() {
+            self.last_movement_state = LastMovement {
+                clear,
+                started_at: Some(self.timer.frames()),
+            };
+        }
+
+        self.stats.score += clear.score(self.stats.level);
         self.stats.cleaned_lines += cleared;
+        self.stats.garbage_cleared += garbage_cleared;
+
+        clear
     }
 
-    pub fn last_movement(&self) -> Option<(&'static str, usize, Duration)> {
-        if let Some(timer) = self.last_movement_state.timer {
-            let elapsed = timer.elapsed();
+    pub fn last_movement(&self) -> Option<(ClearKind, Duration)> {
+        if let Some(started_at) = self.last_movement_state.started_at {
+            let elapsed = self.elapsed_since(started_at);
             if elapsed < COMBO_NOTIFICATION_DURATION {
-                return Some((
-                    self.last_movement_state.name,
-                    self.last_movement_state.b2b_count,
-                    elapsed,
-                ));
+                return Some((self.last_movement_state.clear, elapsed));
             }
         }
         None
     }
 
     pub fn current_combo(&self) -> Option<(usize, Duration)> {
-        if let Some(timer) = self.combo.timer {
-            let elapsed = timer.elapsed();
+        if let Some(started_at) = self.combo.started_at {
+            let elapsed = self.elapsed_since(started_at);
             if elapsed < COMBO_NOTIFICATION_DURATION && self.combo.count > 1 {
                 return Some((self.combo.count - 1, elapsed));
             }
@@ -588,13 +881,32 @@ impl Board {
         None
     }
 
+    fn elapsed_since(&self, frame: u64) -> Duration {
+        frames_to_duration(self.timer.frames().saturating_sub(frame))
+    }
+
+    pub fn timing(&self) -> Timing {
+        Timing::for_level(self.goal, self.stats.level)
+    }
+
+    pub fn next_level_lines(&self) -> usize {
+        let lines_per_level = match self.goal {
+            GameGoal::Marathon { .. } => MARATHON_LEVEL_LINES,
+            _ => GOAL_MULTIPLIER,
+        };
+        (self.stats.level + 1).saturating_sub(self.stats.start_level.max(1)) * lines_per_level
+    }
+
     fn update_level(&mut self) {
         if self.stats.level == 0 {
             return;
         }
 
-        let curr_goal = self.stats.level * GOAL_MULTIPLIER;
-        if self.stats.cleaned_lines >= curr_goal {
+        if matches!(self.goal, GameGoal::Marathon { .. }) && self.is_goal_reached() {
+            return;
+        }
+
+        if self.stats.cleaned_lines >= self.next_level_lines() {
             self.stats.level += 1;
         }
     }
@@ -605,6 +917,11 @@ impl Board {
             return;
         }
 
+        if let Some(interval) = self.timing().row_interval() {
+            self.stats.fall_speed = interval;
+            return;
+        }
+
         if self.stats.level > MAX_FALL_SPEED_LEVEL {
             return;
         }
@@ -620,96 +937,13 @@ impl Board {
     }
 }
 
-impl Widget for &Board {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let board_width = COLUMNS * 2;
-        let board_height = ROWS;
-
-        let start_x = area.x + area.width.saturating_sub(board_width) / 2;
-        let start_y = area.y + area.height.saturating_sub(board_height) / 2;
-
-        let mut set_cell = |x: usize, y: usize, ch: char, style: Style| {
-            let cell_x = start_x + (x as u16 * 2) + 1;
-            let cell_y = start_y + y as u16;
-
-            if cell_x < area.right() && cell_y < area.bottom() {
-                let cell = &mut buf[(cell_x, cell_y)];
-                cell.reset();
-                cell.set_char(ch).set_style(style);
-            }
-        };
-
-        for y in 0..ROWS {
-            for x in 0..COLUMNS {
-                if y == 0 || y == 1 {
-                    set_cell(x as usize, y as usize, ' ', Style::default());
-                } else {
-                    set_cell(x as usize, y as usize, '.', Style::default().dim());
-                }
-            }
-        }
-
-        for y in 0..ROWS {
-            for x in 0..COLUMNS {
-                if let Some(color) = self.board[y as usize][x as usize] {
-                    set_cell(x as usize, y as usize, '■', Style::default().fg(color));
-                }
-            }
-        }
-
-        if let Some(piece) = self.active_piece {
-            let (square_x, square_y) = piece.coord;
-            let active_coords = piece.block.get_coordinates(piece.rotation);
-
-            if let Some((ghost_x, ghost_y)) = self.get_ghost_coord() {
-                if (ghost_x, ghost_y) != (square_x, square_y) {
-                    for (block_x, block_y, _color) in active_coords {
-                        let board_x = ghost_x + block_x as isize;
-                        let board_y = ghost_y + block_y as isize;
-                        let overlaps_active = active_coords.iter().any(|(ax, ay, _)| {
-                            square_x + *ax as isize == board_x && square_y + *ay as isize == board_y
-                        });
-
-                        if !overlaps_active
-                            && board_x >= 0
-                            && board_x < COLUMNS as isize
-                            && board_y >= 0
-                            && board_y < ROWS as isize
-                        {
-                            set_cell(
-                                board_x as usize,
-                                board_y as usize,
-                                '□',
-                                Style::default().fg(Color::White).dim(),
-                            );
-                        }
-                    }
-                }
-            }
-
-            for (block_x, block_y, color) in active_coords {
-                let board_x = square_x + block_x as isize;
-                let board_y = square_y + block_y as isize;
-                if board_x >= 0
-                    && board_x < COLUMNS as isize
-                    && board_y >= 0
-                    && board_y < ROWS as isize
-                {
-                    set_cell(
-                        board_x as usize,
-                        board_y as usize,
-                        '□',
-                        Style::default().fg(color),
-                    );
-                }
-            }
-        }
-    }
-}
-
 pub fn grid_from_str(s: &str) -> Grid {
     let mut grid: Grid = [[None; COLUMNS as usize]; ROWS as usize];
-    let lines: Vec<&str> = s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+    let lines: Vec<&str> = s
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
     let num_lines = lines.len();
     let start_row = (ROWS as usize).saturating_sub(num_lines);
 
@@ -723,38 +957,53 @@ pub fn grid_from_str(s: &str) -> Grid {
                 break;
             }
             grid[grid_row][col_idx] = match c {
-                'X' | '#' => Some(Color::DarkGray),
-                'I' => Some(Color::Cyan),
-                'O' => Some(Color::Yellow),
-                'T' => Some(Color::Magenta),
-                'S' => Some(Color::Green),
-                'Z' => Some(Color::Red),
-                'J' => Some(Color::Blue),
-                'L' => Some(Color::Rgb(255, 127, 0)),
-                _ => None,
+                'X' | '#' => Some(Cell::Garbage),
+                letter => Block::from_letter(letter).map(Cell::Block),
             };
         }
     }
     grid
 }
 
+pub fn grid_to_str(grid: &Grid) -> String {
+    let first_row = grid
+        .iter()
+        .position(|row| row.iter().any(Option::is_some))
+        .unwrap_or(grid.len());
+
+    grid[first_row..]
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Some(Cell::Block(block)) => block.letter(),
+                    Some(Cell::Garbage) => 'X',
+                    None => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub mod presets {
     use super::{grid_from_str, Grid};
 
     pub fn t_spin_double() -> Grid {
         grid_from_str(
-            "XXXX.X.XXX\n\
-             XXXX...XXX\n\
-             XXXXXXXXXX",
+            "....XXXXXX\n\
+             XX...XXXXX\n\
+             XXX.XXXXXX",
         )
     }
 
     pub fn t_spin_triple() -> Grid {
         grid_from_str(
-            "XXXX..XXXX\n\
-             XXXX.XXXXX\n\
+            "XXXXX.....\n\
+             XXX.......\n\
              XXXX.XXXXX\n\
-             XXXX..XXXX",
+             XXXX..XXXX\n\
+             XXXX.XXXXX",
         )
     }
 
@@ -769,7 +1018,9 @@ pub mod presets {
 
     pub fn l_spin() -> Grid {
         grid_from_str(
-            "XXXX..XXXX\n\
+            "XXXXX.....\n\
+             XXX.......\n\
+             XXXX..XXXX\n\
              XXXX.XXXXX\n\
              XXXX..XXXX",
         )
@@ -777,7 +1028,9 @@ pub mod presets {
 
     pub fn j_spin() -> Grid {
         grid_from_str(
-            "XXXX..XXXX\n\
+            ".....XXXXX\n\
+             .......XXX\n\
+             XXXX..XXXX\n\
              XXXXX.XXXX\n\
              XXXX..XXXX",
         )
@@ -799,3 +1052,247 @@ pub mod presets {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+
+    fn piece(block: Block, rotation: Rotation, coord: (isize, isize)) -> ActivePiece {
+        ActivePiece {
+            rotation,
+            ..ActivePiece::new(block, coord)
+        }
+    }
+
+    fn rotate(grid: &str, start: ActivePiece, direction: RotationDirection) -> ActivePiece {
+        let mut board = Board::new();
+        board.new_with_grid(grid_from_str(grid));
+        board.active_piece = Some(start);
+        assert!(board.rotate_block(direction));
+        board.active_piece.unwrap()
+    }
+
+    fn lock(grid: Grid, piece: ActivePiece) -> ClearKind {
+        let mut board = Board::new();
+        board.new_with_grid(grid);
+        board.active_piece = Some(piece);
+        board.lock_current_block();
+        board.last_clear.unwrap()
+    }
+
+    fn spun(block: Block, rotation: Rotation, coord: (isize, isize)) -> ActivePiece {
+        ActivePiece {
+            last_action_was_rotation: true,
+            ..piece(block, rotation, coord)
+        }
+    }
+
+    fn full_spin(block: Block) -> Option<Spin> {
+        Some(Spin { block, mini: false })
+    }
+
+    #[test]
+    fn kicks_i_piece_off_the_floor_and_wall() {
+        let start = piece(Block::Line, Rotation::Deg0, (6, 20));
+        let rotated = rotate("XXXXXX....", start, RotationDirection::Clockwise);
+        assert_eq!(rotated.rotation, Rotation::Deg90);
+        assert_eq!(rotated.coord, (7, 18));
+    }
+
+    #[test]
+    fn kicks_t_piece_into_triple_slot() {
+        let grid = "....X.....\n\
+                    ..........\n\
+                    XXXX.XXXXX\n\
+                    XXXX..XXXX\n\
+                    XXXX.XXXXX";
+        let start = piece(Block::T, Rotation::Deg0, (4, 17));
+        let rotated = rotate(grid, start, RotationDirection::Clockwise);
+        assert_eq!(rotated.rotation, Rotation::Deg90);
+        assert_eq!(rotated.coord, (3, 19));
+        assert!(rotated.last_rotation_was_far_kick);
+    }
+
+    #[test]
+    fn kicks_180_rotation_off_the_floor() {
+        let start = piece(Block::T, Rotation::Deg0, (4, 20));
+        let rotated = rotate("", start, RotationDirection::Half);
+        assert_eq!(rotated.rotation, Rotation::Deg180);
+        assert_eq!(rotated.coord, (4, 19));
+    }
+
+    #[test]
+    fn classifies_all_spins_on_preset_boards() {
+        let cases = [
+            (presets::l_spin(), spun(Block::L, Rotation::Deg90, (3, 19))),
+            (presets::j_spin(), spun(Block::J, Rotation::Deg270, (4, 19))),
+            (presets::s_spin(), spun(Block::S, Rotation::Deg0, (3, 19))),
+            (presets::z_spin(), spun(Block::Z, Rotation::Deg0, (4, 19))),
+        ];
+        for (grid, piece) in cases {
+            let clear = lock(grid, piece);
+            assert_eq!(clear.spin, full_spin(piece.block), "{:?}", piece.block);
+            assert_eq!(clear.lines, 2, "{:?}", piece.block);
+        }
+    }
+
+    #[test]
+    fn classifies_i_spin_under_a_roof() {
+        let grid = grid_from_str(
+            "XXXXX.....\n\
+             X....XXXXX\n\
+             XXXXXXXXX.",
+        );
+        let clear = lock(grid, spun(Block::Line, Rotation::Deg0, (1, 19)));
+        assert_eq!(clear.spin, full_spin(Block::Line));
+        assert_eq!(clear.lines, 1);
+    }
+
+    #[test]
+    fn ignores_spins_without_rotation() {
+        let clear = lock(presets::s_spin(), piece(Block::S, Rotation::Deg0, (3, 19)));
+        assert_eq!(clear.spin, None);
+        assert_eq!(clear.lines, 2);
+    }
+
+    #[test]
+    fn classifies_full_t_spins() {
+        let double = lock(
+            presets::t_spin_double(),
+            spun(Block::T, Rotation::Deg180, (2, 19)),
+        );
+        assert_eq!(double.spin, full_spin(Block::T));
+        assert_eq!(double.lines, 2);
+
+        let triple = lock(
+            presets::t_spin_triple(),
+            spun(Block::T, Rotation::Deg90, (3, 19)),
+        );
+        assert_eq!(triple.spin, full_spin(Block::T));
+        assert_eq!(triple.lines, 3);
+    }
+
+    #[test]
+    fn classifies_t_spin_mini_with_one_front_corner() {
+        let clear = lock(
+            presets::t_spin_double(),
+            spun(Block::T, Rotation::Deg0, (2, 19)),
+        );
+        assert_eq!(
+            clear.spin,
+            Some(Spin {
+                block: Block::T,
+                mini: true
+            })
+        );
+        assert_eq!(clear.lines, 1);
+    }
+
+    #[test]
+    fn promotes_far_kick_mini_to_full_t_spin() {
+        let piece = ActivePiece {
+            last_rotation_was_far_kick: true,
+            ..spun(Block::T, Rotation::Deg0, (2, 19))
+        };
+        let clear = lock(presets::t_spin_double(), piece);
+        assert_eq!(clear.spin, full_spin(Block::T));
+    }
+
+    fn script(pieces: u64) -> Vec<(u64, GameAction)> {
+        let mut actions = Vec::new();
+        for piece in 0..pieces {
+            let mut frame = piece * 40;
+            let mut push = |action| {
+                actions.push((frame, action));
+                frame += 3;
+            };
+            if piece % 5 == 4 {
+                push(GameAction::Hold);
+            }
+            match piece % 3 {
+                0 => push(GameAction::RotateCW),
+                1 => push(GameAction::Rotate180),
+                _ => {}
+            }
+            let shift = (piece * 7 % 9) as isize - 4;
+            for _ in 0..shift.unsigned_abs() {
+                push(if shift < 0 {
+                    GameAction::MoveLeft
+                } else {
+                    GameAction::MoveRight
+                });
+            }
+            push(if piece % 4 == 0 {
+                GameAction::SoftDrop
+            } else {
+                GameAction::HardDrop
+            });
+        }
+        actions
+    }
+
+    fn start(seed: u64) -> (Board, BlocksManager) {
+        let mut board = Board::new();
+        board.new_with_grid_and_gravity(grid_from_str(&"XX......XX\n".repeat(12)), 1);
+        (board, BlocksManager::with_seed(seed))
+    }
+
+    fn play(
+        board: &mut Board,
+        blocks_manager: &mut BlocksManager,
+        actions: &[(u64, GameAction)],
+        frames: Range<u64>,
+    ) {
+        for frame in frames {
+            for (_, action) in actions.iter().filter(|(at, _)| *at == frame) {
+                board.apply_action(*action, blocks_manager);
+            }
+            if board.tick(blocks_manager) != TickOutcome::Playing {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn replays_frame_stamped_actions_deterministically() {
+        let actions = script(80);
+        let (mut first, mut first_blocks) = start(144);
+        let (mut second, mut second_blocks) = start(144);
+        play(&mut first, &mut first_blocks, &actions, 0..3000);
+        play(&mut second, &mut second_blocks, &actions, 0..3000);
+
+        assert!(first.stats.cleaned_lines > 0);
+        assert_eq!(first.grid(), second.grid());
+        assert_eq!(first.stats, second.stats);
+        assert_eq!(first.timer.frames(), second.timer.frames());
+        assert_eq!(
+            first_blocks.get_next_blocks(),
+            second_blocks.get_next_blocks()
+        );
+    }
+
+    #[test]
+    fn resumes_saved_game_with_the_same_pieces() {
+        let actions = script(80);
+        let (mut board, mut blocks_manager) = start(144);
+        play(&mut board, &mut blocks_manager, &actions, 0..400);
+
+        let saved = serde_json::to_string(&(&board, &blocks_manager)).unwrap();
+        let (mut restored, mut restored_blocks): (Board, BlocksManager) =
+            serde_json::from_str(&saved).unwrap();
+        assert_eq!(
+            restored_blocks.get_next_blocks(),
+            blocks_manager.get_next_blocks()
+        );
+
+        play(&mut board, &mut blocks_manager, &actions, 400..1000);
+        play(&mut restored, &mut restored_blocks, &actions, 400..1000);
+        assert_eq!(restored.grid(), board.grid());
+        assert_eq!(restored.stats, board.stats);
+        assert_eq!(
+            restored_blocks.get_next_blocks(),
+            blocks_manager.get_next_blocks()
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::Block;
+
+const COMBO_ATTACK: [usize; 12] = [0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5];
+const PERFECT_CLEAR_ATTACK: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spin {
+    pub block: Block,
+    pub mini: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ClearKind {
+    pub spin: Option<Spin>,
+    pub lines: usize,
+    pub perfect_clear: bool,
+    pub b2b: usize,
+    pub combo: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    Spin { block: Block, lines: usize },
+    Quad,
+    PerfectClear,
+}
+
+impl Objective {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase().replace(['-', '_'], " ");
+        let words: Vec<&str> = s.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["quad"] | ["tetris"] => Some(Self::Quad),
+            ["perfect", "clear"] | ["pc"] | ["all", "clear"] => Some(Self::PerfectClear),
+            ["tss"] => Some(Self::Spin {
+                block: Block::T,
+                lines: 1,
+            }),
+            ["tsd"] => Some(Self::Spin {
+                block: Block::T,
+                lines: 2,
+            }),
+            ["tst"] => Some(Self::Spin {
+                block: Block::T,
+                lines: 3,
+            }),
+            [letter, "spin", lines] => {
+                let mut letters = letter.chars();
+                let block = Block::from_letter(letters.next()?.to_ascii_uppercase())?;
+                if letters.next().is_some() {
+                    return None;
+                }
+                let lines = match *lines {
+                    "single" => 1,
+                    "double" => 2,
+                    "triple" => 3,
+                    _ => return None,
+                };
+                Some(Self::Spin { block, lines })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_met(&self, clear: &ClearKind) -> bool {
+        match *self {
+            Self::Spin { block, lines } => {
+                clear.lines == lines
+                    && clear
+                        .spin
+                        .is_some_and(|spin| spin.block == block && !spin.mini)
+            }
+            Self::Quad => clear.lines == 4,
+            Self::PerfectClear => clear.perfect_clear,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match *self {
+            Self::Spin { block, lines } => {
+                let lines = match lines {
+                    1 => "Single",
+                    2 => "Double",
+                    _ => "Triple",
+                };
+                format!("{}-Spin {}", block.letter(), lines)
+            }
+            Self::Quad => "Quad".to_string(),
+            Self::PerfectClear => "Perfect Clear".to_string(),
+        }
+    }
+}
+
+impl ClearKind {
+    pub fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.spin.is_some() && self.lines > 0)
+    }
+
+    pub fn is_notable(&self) -> bool {
+        self.lines > 0 || self.spin.is_some()
+    }
+
+    pub fn is_b2b(&self) -> bool {
+        self.b2b > 0
+    }
+
+    fn base_score(&self) -> usize {
+        match self.spin {
+            Some(Spin { block, mini }) => match (block, mini, self.lines) {
+                (Block::T, false, 0) => 400,
+                (Block::T, false, 1) => 800,
+                (Block::T, false, 2) => 1200,
+                (Block::T, false, _) => 1600,
+                (Block::T, true, 0) => 100,
+                (Block::T, true, 1) => 200,
+                (Block::T, true, _) => 400,
+                (_, _, 0) => 100,
+                (_, _, 1) => 200,
+                (_, _, 2) => 400,
+                (_, _, _) => 800,
+            },
+            None => match self.lines {
+                0 => 0,
+                1 => 100,
+                2 => 300,
+                3 => 500,
+                _ => 800,
+            },
+        }
+    }
+
+    fn perfect_clear_bonus(&self) -> usize {
+        if !self.perfect_clear {
+            return 0;
+        }
+
+        match self.lines {
+            1 => 800,
+            2 => 1200,
+            3 => 1800,
+            _ => 2000,
+        }
+    }
+
+    pub fn attack(&self) -> usize {
+        if self.lines == 0 {
+            return 0;
+        }
+
+        let base = match self.spin {
+            Some(Spin {
+                block: Block::T,
+                mini: false,
+            }) => self.lines * 2,
+            Some(Spin {
+                block: Block::T,
+                mini: true,
+            }) => self.lines - 1,
+            _ => match self.lines {
+                1 => 0,
+                2 => 1,
+                3 => 2,
+                _ => 4,
+            },
+        };
+        let b2b = usize::from(self.is_b2b());
+        let combo = COMBO_ATTACK[self.combo.min(COMBO_ATTACK.len() - 1)];
+        let perfect_clear = if self.perfect_clear {
+            PERFECT_CLEAR_ATTACK
+        } else {
+            0
+        };
+
+        base + b2b + combo + perfect_clear
+    }
+
+    pub fn score(&self, level: usize) -> usize {
+        let mut base_score = self.base_score();
+        if self.is_b2b() {
+            base_score = (base_score as f64 * 1.5) as usize;
+        }
+        base_score += self.perfect_clear_bonus();
+
+        let combo_bonus = 50 * self.combo * level;
+        base_score * level + combo_bonus
+    }
+}
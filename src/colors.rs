@@ -1,7 +1,34 @@
 use ratatui::style::Color;
+use tetrus::{blocks::Block, board::Cell};
 
 pub const ORANGE: Color = Color::Rgb(255, 127, 0);
 pub const GOLD: Color = Color::Rgb(255, 215, 0);
 pub const SILVER: Color = Color::Rgb(192, 192, 192);
 pub const BRONZE: Color = Color::Rgb(205, 127, 50);
 
+pub trait ToColor {
+    fn color(self) -> Color;
+}
+
+impl ToColor for Block {
+    fn color(self) -> Color {
+        match self {
+            Self::Square => Color::Yellow,
+            Self::T => Color::Magenta,
+            Self::Line => Color::Cyan,
+            Self::L => ORANGE,
+            Self::J => Color::Blue,
+            Self::Z => Color::Red,
+            Self::S => Color::Green,
+        }
+    }
+}
+
+impl ToColor for Cell {
+    fn color(self) -> Color {
+        match self {
+            Self::Block(block) => block.color(),
+            Self::Garbage => Color::DarkGray,
+        }
+    }
+}
@@ -3,8 +3,22 @@ use std::time::Duration;
 pub const COLUMNS: u16 = 10;
 pub const ROWS: u16 = 22;
 pub const GOAL_MULTIPLIER: usize = 5;
+pub const SPRINT_LINES: usize = 40;
+pub const MARATHON_LINES: usize = 150;
+pub const MARATHON_LEVEL_LINES: usize = 10;
+pub const MASTER_LINES: usize = 100;
+pub const BLITZ_DURATION: Duration = Duration::from_secs(120);
+pub const DIG_ROWS: [usize; 2] = [10, 18];
+pub const DIG_MESSINESS_STEP: u32 = 10;
+pub const SURVIVAL_MESSINESS: u32 = 40;
+pub const SURVIVAL_RISE_FRAMES: u64 = 480;
+pub const SURVIVAL_MIN_RISE_FRAMES: u64 = 90;
+pub const SURVIVAL_RISE_STEP_FRAMES: u64 = 15;
 pub const MAX_FALL_SPEED_LEVEL: usize = 20;
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / 60);
 pub const LOCK_DELAY_FRAMES_DURATION: Duration = Duration::from_millis(500);
 pub const MAX_DELAY_FRAMES_LOCK_RESETS: usize = 15;
+pub const GARBAGE_DELAY_DURATION: Duration = Duration::from_millis(500);
 pub const COMBO_NOTIFICATION_DURATION: Duration = Duration::from_millis(3000);
 pub const COMBO_NOTIFICATION_FADE_DELAY: Duration = Duration::from_millis(500);
+pub const AI_PLACEMENT_DELAY_FRAMES: u32 = 10;
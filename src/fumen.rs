@@ -0,0 +1,501 @@
+use crate::{
+    blocks::{Block, Rotation},
+    blocks_manager::BlocksManager,
+    board::{ActivePiece, Board, Cell, Grid, TickOutcome},
+    constants::{COLUMNS, ROWS},
+    replay::{Replay, ReplayPlayer},
+};
+
+pub const FUMEN_URL: &str = "https://fumen.zui.jp/?";
+
+const VERSION: &str = "115@";
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const FIELD_TOP: usize = 23;
+const FIELD_WIDTH: usize = 10;
+const FIELD_BLOCKS: usize = (FIELD_TOP + 1) * FIELD_WIDTH;
+const UNCHANGED_FIELD: usize = 8 * FIELD_BLOCKS + FIELD_BLOCKS - 1;
+const MAX_REPEAT: u8 = 63;
+const GRAY: u8 = 8;
+const GRID_ROW_OFFSET: usize = FIELD_TOP - ROWS as usize;
+
+type Field = [u8; FIELD_BLOCKS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub block: Block,
+    pub rotation: Rotation,
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub field: Grid,
+    pub operation: Option<Operation>,
+}
+
+impl Operation {
+    pub fn from_piece(piece: &ActivePiece) -> Option<Self> {
+        let cells = piece.cells().map(|(x, row)| (x, ROWS as isize - 1 - row));
+        if cells.iter().any(|&(x, y)| field_index(x, y).is_none()) {
+            return None;
+        }
+
+        cells.iter().find_map(|&(x, y)| {
+            let operation = Self {
+                block: piece.block,
+                rotation: piece.rotation,
+                x,
+                y,
+            };
+            let is_same = operation.cells().iter().all(|cell| cells.contains(cell));
+            is_same.then_some(operation)
+        })
+    }
+
+    fn cells(&self) -> [(isize, isize); 4] {
+        let shape: [(isize, isize); 4] = match self.block {
+            Block::Line => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+            Block::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+            Block::Square => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            Block::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+            Block::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+            Block::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+            Block::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+        };
+
+        shape.map(|(dx, dy)| {
+            let (dx, dy) = match self.rotation {
+                Rotation::Deg0 => (dx, dy),
+                Rotation::Deg90 => (dy, -dx),
+                Rotation::Deg180 => (-dx, -dy),
+                Rotation::Deg270 => (-dy, dx),
+            };
+            (self.x + dx, self.y + dy)
+        })
+    }
+
+    // fumen stores I, O, S and Z by the corner of their symmetric twin, not by their SRS center
+    fn raw_offset(&self) -> (isize, isize) {
+        match (self.block, self.rotation) {
+            (Block::Square, Rotation::Deg0) => (0, 1),
+            (Block::Square, Rotation::Deg180) => (-1, 0),
+            (Block::Square, Rotation::Deg270) => (-1, 1),
+            (Block::Line, Rotation::Deg180) => (-1, 0),
+            (Block::Line, Rotation::Deg270) => (0, 1),
+            (Block::S, Rotation::Deg0) => (0, 1),
+            (Block::S, Rotation::Deg90) => (1, 0),
+            (Block::Z, Rotation::Deg0) => (0, 1),
+            (Block::Z, Rotation::Deg270) => (-1, 0),
+            _ => (0, 0),
+        }
+    }
+}
+
+pub fn decode(data: &str) -> Option<Vec<Page>> {
+    let start = data.find(VERSION)? + VERSION.len();
+    let mut values = data[start..]
+        .chars()
+        .filter(|c| *c != '?' && !c.is_whitespace())
+        .map(|c| ALPHABET.iter().position(|&a| a as char == c))
+        .collect::<Option<Vec<usize>>>()?
+        .into_iter();
+    let mut poll = |digits: u32| -> Option<usize> {
+        (0..digits).try_fold(0, |value, i| Some(value + values.next()? * 64usize.pow(i)))
+    };
+
+    let mut pages = Vec::new();
+    let mut prev: Field = [0; FIELD_BLOCKS];
+    let mut repeat = 0;
+
+    while let Some(first) = poll(2) {
+        let mut field = prev;
+        if repeat > 0 {
+            repeat -= 1;
+            let action = first + poll(1)? * 64 * 64;
+            decode_page(&mut pages, &mut prev, field, action, &mut poll)?;
+            continue;
+        }
+
+        let mut index = 0;
+        let mut run = first;
+        loop {
+            let diff = run / FIELD_BLOCKS;
+            let count = run % FIELD_BLOCKS + 1;
+            for cell in field.get_mut(index..index + count)? {
+                *cell = (*cell as usize + diff).checked_sub(8).filter(|&v| v <= 8)? as u8;
+            }
+            index += count;
+            if index >= FIELD_BLOCKS {
+                break;
+            }
+            run = poll(2)?;
+        }
+
+        if first == UNCHANGED_FIELD {
+            repeat = poll(1)?;
+        }
+
+        let action = poll(3)?;
+        decode_page(&mut pages, &mut prev, field, action, &mut poll)?;
+    }
+
+    (!pages.is_empty()).then_some(pages)
+}
+
+fn decode_page(
+    pages: &mut Vec<Page>,
+    prev: &mut Field,
+    mut field: Field,
+    action: usize,
+    poll: &mut impl FnMut(u32) -> Option<usize>,
+) -> Option<()> {
+    let piece = action % 8;
+    let rotation = action / 8 % 4;
+    let coordinate = action / 32 % FIELD_BLOCKS;
+    let flags = action / 32 / FIELD_BLOCKS;
+    let rise = flags & 1 != 0;
+    let mirror = flags & 2 != 0;
+    let comment = flags & 8 != 0;
+    let lock = flags & 16 == 0;
+
+    if comment {
+        let length = poll(2)?;
+        for _ in 0..length.div_ceil(4) {
+            poll(5)?;
+        }
+    }
+
+    let operation = match block_of(piece as u8) {
+        Some(block) => {
+            let rotation = match rotation {
+                0 => Rotation::Deg180,
+                1 => Rotation::Deg90,
+                2 => Rotation::Deg0,
+                _ => Rotation::Deg270,
+            };
+            let mut operation = Operation {
+                block,
+                rotation,
+                x: (coordinate % FIELD_WIDTH) as isize,
+                y: FIELD_TOP as isize - (coordinate / FIELD_WIDTH) as isize - 1,
+            };
+            let (dx, dy) = operation.raw_offset();
+            operation.x -= dx;
+            operation.y -= dy;
+            if operation
+                .cells()
+                .iter()
+                .any(|&(x, y)| field_index(x, y).is_none())
+            {
+                return None;
+            }
+            Some(operation)
+        }
+        None => None,
+    };
+
+    pages.push(Page {
+        field: grid_of(&field),
+        operation,
+    });
+
+    if lock {
+        if let Some(operation) = operation {
+            for (x, y) in operation.cells() {
+                *field.get_mut(field_index(x, y)?)? = value_of(Some(Cell::Block(operation.block)));
+            }
+        }
+        clear_lines(&mut field);
+        if rise {
+            field.copy_within(FIELD_WIDTH.., 0);
+            field[FIELD_TOP * FIELD_WIDTH..].fill(0);
+        }
+        if mirror {
+            for row in field[..FIELD_TOP * FIELD_WIDTH].chunks_mut(FIELD_WIDTH) {
+                row.reverse();
+            }
+        }
+    }
+
+    *prev = field;
+    Some(())
+}
+
+pub fn encode(pages: &[Page]) -> String {
+    let mut values: Vec<usize> = Vec::new();
+    let mut prev: Field = [0; FIELD_BLOCKS];
+    let mut repeat_index: Option<usize> = None;
+
+    for (i, page) in pages.iter().enumerate() {
+        let mut field = field_of(&page.field);
+
+        let diffs: Vec<usize> = field
+            .iter()
+            .zip(prev.iter())
+            .map(|(&current, &previous)| current as usize + 8 - previous as usize)
+            .collect();
+
+        if diffs.iter().all(|&diff| diff == 8) {
+            match repeat_index {
+                Some(index) if values[index] < MAX_REPEAT as usize => values[index] += 1,
+                _ => {
+                    push_value(&mut values, UNCHANGED_FIELD, 2);
+                    repeat_index = Some(values.len());
+                    values.push(0);
+                }
+            }
+        } else {
+            let mut index = 0;
+            while index < FIELD_BLOCKS {
+                let diff = diffs[index];
+                let count = diffs[index..].iter().take_while(|&&d| d == diff).count();
+                push_value(&mut values, diff * FIELD_BLOCKS + count - 1, 2);
+                index += count;
+            }
+            repeat_index = None;
+        }
+
+        let (piece, rotation, coordinate) = match page.operation {
+            Some(operation) => {
+                let (dx, dy) = operation.raw_offset();
+                let rotation = match operation.rotation {
+                    Rotation::Deg180 => 0,
+                    Rotation::Deg90 => 1,
+                    Rotation::Deg0 => 2,
+                    Rotation::Deg270 => 3,
+                };
+                let coordinate = field_index(operation.x + dx, operation.y + dy).unwrap_or(0);
+                let piece = value_of(Some(Cell::Block(operation.block)));
+                (piece as usize, rotation, coordinate)
+            }
+            None => (0, 0, 0),
+        };
+        let colorize = (i == 0) as usize;
+        let flags = colorize * 4;
+        push_value(
+            &mut values,
+            piece + 8 * (rotation + 4 * (coordinate + FIELD_BLOCKS * flags)),
+            3,
+        );
+
+        if let Some(operation) = page.operation {
+            for (x, y) in operation.cells() {
+                if let Some(cell) = field_index(x, y).and_then(|index| field.get_mut(index)) {
+                    *cell = value_of(Some(Cell::Block(operation.block)));
+                }
+            }
+        }
+        clear_lines(&mut field);
+        prev = field;
+    }
+
+    let data: String = values.iter().map(|&v| ALPHABET[v] as char).collect();
+    let mut result = format!("v{}", VERSION);
+    for (i, c) in data.chars().enumerate() {
+        if i >= 42 && (i - 42) % 47 == 0 {
+            result.push('?');
+        }
+        result.push(c);
+    }
+    result
+}
+
+pub fn encode_grid(grid: &Grid, piece: Option<&ActivePiece>) -> String {
+    encode(&[Page {
+        field: *grid,
+        operation: piece.and_then(Operation::from_piece),
+    }])
+}
+
+pub fn encode_replay(replay: &Replay) -> String {
+    let mut board = Board::new();
+    board.new_game_with_goal(replay.goal);
+    board.setup_goal_garbage(replay.seed);
+    let mut blocks_manager = BlocksManager::new();
+    blocks_manager.reset_with_seed(replay.seed);
+    let mut player = ReplayPlayer::new(replay.clone());
+    let mut pages = Vec::new();
+
+    loop {
+        let frame = board.timer.frames();
+        if player.is_finished(frame) {
+            break;
+        }
+
+        while let Some(action) = player.next_action(frame) {
+            record_lock(&mut pages, &mut board, |board| {
+                board.apply_action(action, &mut blocks_manager);
+            });
+        }
+
+        let mut outcome = TickOutcome::Playing;
+        record_lock(&mut pages, &mut board, |board| {
+            outcome = board.tick(&mut blocks_manager);
+        });
+        if matches!(outcome, TickOutcome::ToppedOut | TickOutcome::GoalReached) {
+            break;
+        }
+    }
+
+    pages.push(Page {
+        field: *board.grid(),
+        operation: None,
+    });
+    encode(&pages)
+}
+
+fn record_lock(pages: &mut Vec<Page>, board: &mut Board, f: impl FnOnce(&mut Board)) {
+    let field = *board.grid();
+    let landing = board
+        .active_piece
+        .zip(board.get_ghost_coord())
+        .map(|(piece, coord)| ActivePiece { coord, ..piece });
+
+    f(board);
+
+    if *board.grid() != field {
+        pages.push(Page {
+            field,
+            operation: landing.as_ref().and_then(Operation::from_piece),
+        });
+    }
+}
+
+fn field_index(x: isize, y: isize) -> Option<usize> {
+    let is_inside = (0..FIELD_WIDTH as isize).contains(&x) && (0..FIELD_TOP as isize).contains(&y);
+    is_inside.then(|| (FIELD_TOP - 1 - y as usize) * FIELD_WIDTH + x as usize)
+}
+
+fn field_of(grid: &Grid) -> Field {
+    let mut field = [0; FIELD_BLOCKS];
+    for (row, cells) in grid.iter().enumerate() {
+        for (x, cell) in cells.iter().enumerate() {
+            field[(row + GRID_ROW_OFFSET) * FIELD_WIDTH + x] = value_of(*cell);
+        }
+    }
+    field
+}
+
+fn grid_of(field: &Field) -> Grid {
+    let mut grid: Grid = [[None; COLUMNS as usize]; ROWS as usize];
+    for (row, cells) in grid.iter_mut().enumerate() {
+        for (x, cell) in cells.iter_mut().enumerate() {
+            *cell = cell_of(field[(row + GRID_ROW_OFFSET) * FIELD_WIDTH + x]);
+        }
+    }
+    grid
+}
+
+fn clear_lines(field: &mut Field) {
+    let rows: Vec<[u8; FIELD_WIDTH]> = field[..FIELD_TOP * FIELD_WIDTH]
+        .chunks(FIELD_WIDTH)
+        .filter(|row| row.contains(&0))
+        .map(|row| row.try_into().unwrap())
+        .collect();
+    let cleared = FIELD_TOP - rows.len();
+
+    field[..cleared * FIELD_WIDTH].fill(0);
+    for (i, row) in rows.iter().enumerate() {
+        let start = (cleared + i) * FIELD_WIDTH;
+        field[start..start + FIELD_WIDTH].copy_from_slice(row);
+    }
+}
+
+fn value_of(cell: Option<Cell>) -> u8 {
+    match cell {
+        None => 0,
+        Some(Cell::Block(Block::Line)) => 1,
+        Some(Cell::Block(Block::L)) => 2,
+        Some(Cell::Block(Block::Square)) => 3,
+        Some(Cell::Block(Block::Z)) => 4,
+        Some(Cell::Block(Block::T)) => 5,
+        Some(Cell::Block(Block::J)) => 6,
+        Some(Cell::Block(Block::S)) => 7,
+        Some(Cell::Garbage) => GRAY,
+    }
+}
+
+fn block_of(value: u8) -> Option<Block> {
+    match cell_of(value)? {
+        Cell::Block(block) => Some(block),
+        Cell::Garbage => None,
+    }
+}
+
+fn cell_of(value: u8) -> Option<Cell> {
+    match value {
+        1 => Some(Cell::Block(Block::Line)),
+        2 => Some(Cell::Block(Block::L)),
+        3 => Some(Cell::Block(Block::Square)),
+        4 => Some(Cell::Block(Block::Z)),
+        5 => Some(Cell::Block(Block::T)),
+        6 => Some(Cell::Block(Block::J)),
+        7 => Some(Cell::Block(Block::S)),
+        GRAY => Some(Cell::Garbage),
+        _ => None,
+    }
+}
+
+fn push_value(values: &mut Vec<usize>, mut value: usize, digits: u32) {
+    for _ in 0..digits {
+        values.push(value % ALPHABET.len());
+        value /= ALPHABET.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_grid() -> Grid {
+        [[None; COLUMNS as usize]; ROWS as usize]
+    }
+
+    #[test]
+    fn decodes_empty_field() {
+        let pages = decode("v115@vhAAgH").unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].field, empty_grid());
+        assert_eq!(pages[0].operation, None);
+    }
+
+    #[test]
+    fn round_trips_pages() {
+        let mut field = empty_grid();
+        let bottom = ROWS as usize - 1;
+        field[bottom][1..].fill(Some(Cell::Garbage));
+        field[bottom - 1][0] = Some(Cell::Block(Block::S));
+        let pages = vec![
+            Page {
+                field,
+                operation: Some(Operation {
+                    block: Block::T,
+                    rotation: Rotation::Deg0,
+                    x: 4,
+                    y: 5,
+                }),
+            },
+            Page {
+                field,
+                operation: None,
+            },
+        ];
+
+        assert_eq!(decode(&encode(&pages)), Some(pages));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for data in [
+            "",
+            "v115@",
+            "v110@vhAAgH",
+            "v115@vhA",
+            "v115@vh!!gH",
+            "v115@vhA11B",
+        ] {
+            assert_eq!(decode(data), None, "{data}");
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(not(feature = "vhs"))]
+use crate::scores::HighScores;
+use std::io;
+#[cfg(not(feature = "vhs"))]
+use std::{fs, path::PathBuf};
+
+#[cfg(not(feature = "vhs"))]
+pub fn storage_path() -> PathBuf {
+    HighScores::storage_path().with_file_name("fumen.txt")
+}
+
+#[cfg(not(feature = "vhs"))]
+pub fn save(link: &str) -> io::Result<()> {
+    let path = storage_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, format!("{}\n", link))
+}
+
+#[cfg(feature = "vhs")]
+pub fn save(_link: &str) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use rand::{rngs::SmallRng, RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    COLUMNS, SURVIVAL_MESSINESS, SURVIVAL_MIN_RISE_FRAMES, SURVIVAL_RISE_FRAMES,
+    SURVIVAL_RISE_STEP_FRAMES,
+};
+
+pub const MAX_MESSINESS: u32 = 100;
+const GARBAGE_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "GarbageGeneratorState", into = "GarbageGeneratorState")]
+pub struct GarbageGenerator {
+    rng: SmallRng,
+    seed: u64,
+    messiness: u32,
+    hole: usize,
+    holes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GarbageGeneratorState {
+    seed: u64,
+    messiness: u32,
+    holes: u64,
+}
+
+impl From<GarbageGenerator> for GarbageGeneratorState {
+    fn from(generator: GarbageGenerator) -> Self {
+        Self {
+            seed: generator.seed,
+            messiness: generator.messiness,
+            holes: generator.holes,
+        }
+    }
+}
+
+impl From<GarbageGeneratorState> for GarbageGenerator {
+    fn from(state: GarbageGeneratorState) -> Self {
+        let mut generator = Self::new(state.seed, state.messiness);
+        for _ in 0..state.holes {
+            generator.next_hole();
+        }
+        generator
+    }
+}
+
+impl GarbageGenerator {
+    pub fn new(seed: u64, messiness: u32) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed ^ GARBAGE_SEED_SALT);
+        let hole = rng.random_range(0..COLUMNS as usize);
+        Self {
+            rng,
+            seed,
+            messiness: messiness.min(MAX_MESSINESS),
+            hole,
+            holes: 0,
+        }
+    }
+
+    pub fn next_hole(&mut self) -> usize {
+        self.holes += 1;
+        if self.rng.random_ratio(self.messiness, MAX_MESSINESS) {
+            let shift = self.rng.random_range(1..COLUMNS as usize);
+            self.hole = (self.hole + shift) % COLUMNS as usize;
+        }
+        self.hole
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RisingGarbage {
+    garbage: GarbageGenerator,
+    accelerating: bool,
+    interval: u64,
+    next_rise: u64,
+}
+
+impl RisingGarbage {
+    pub fn new(seed: u64, accelerating: bool) -> Self {
+        Self {
+            garbage: GarbageGenerator::new(seed, SURVIVAL_MESSINESS),
+            accelerating,
+            interval: SURVIVAL_RISE_FRAMES,
+            next_rise: SURVIVAL_RISE_FRAMES,
+        }
+    }
+
+    pub fn next_rise(&self) -> u64 {
+        self.next_rise
+    }
+
+    pub fn next_hole(&mut self, frame: u64) -> Option<usize> {
+        if frame < self.next_rise {
+            return None;
+        }
+
+        if self.accelerating {
+            self.interval = self
+                .interval
+                .saturating_sub(SURVIVAL_RISE_STEP_FRAMES)
+                .max(SURVIVAL_MIN_RISE_FRAMES);
+        }
+        self.next_rise += self.interval;
+        Some(self.garbage.next_hole())
+    }
+}
@@ -0,0 +1,15 @@
+pub mod action;
+pub mod ai;
+pub mod blocks;
+pub mod blocks_manager;
+pub mod board;
+pub mod clear;
+pub mod constants;
+pub mod fumen;
+pub mod garbage;
+pub mod netplay;
+pub mod preset;
+pub mod replay;
+pub mod tbp;
+pub mod timer;
+pub mod timing;
@@ -1,18 +1,90 @@
-mod blocks;
-mod blocks_manager;
-mod board;
 mod colors;
-mod constants;
+mod fumens;
+mod presets;
+mod replays;
+mod saved_game;
 mod scores;
+mod settings;
 mod tui;
 mod utils;
 
+use tetrus::{
+    netplay::{NetPeer, DEFAULT_PORT},
+    tbp::{TbpBot, TbpSession},
+};
+
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    let bot_command = match args.iter().position(|arg| arg == "--tbp") {
+        Some(index) => args.split_off(index).split_off(1),
+        None => Vec::new(),
+    };
+
+    if !bot_command.is_empty() && args.iter().any(|arg| arg == "--host" || arg == "--join") {
+        eprintln!("`--tbp` cannot be combined with `--host` or `--join`");
+        std::process::exit(1);
+    }
+
+    let bot = if bot_command.is_empty() {
+        None
+    } else {
+        match TbpBot::spawn(&bot_command) {
+            Ok(bot) => Some(TbpSession::new(bot)),
+            Err(e) => {
+                eprintln!("Could not start bot `{}`: {}", bot_command.join(" "), e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let online = connect_online(&args);
+
     ratatui::run(|terminal| {
-        tui::Game::new()
-            .run(terminal)
-            .expect("Error at some point, idk.");
+        let mut game = tui::Game::new();
+        if let Some(bot) = bot {
+            game.watch_bot(bot);
+        }
+        if let Some((peer, seed)) = online {
+            game.play_online(peer, seed);
+        }
+        game.run(terminal).expect("Error at some point, idk.");
     });
 }
 
+fn connect_online(args: &[String]) -> Option<(NetPeer, u64)> {
+    let value_after = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .map(|index| args.get(index + 1).filter(|arg| !arg.starts_with("--")))
+    };
+
+    let result = if let Some(port) = value_after("--host") {
+        let port = match port {
+            Some(port) => port.parse().unwrap_or_else(|_| {
+                eprintln!("Invalid port `{}`", port);
+                std::process::exit(1);
+            }),
+            None => DEFAULT_PORT,
+        };
+        println!("Waiting for an opponent on port {}...", port);
+        NetPeer::host(port)
+    } else if let Some(address) = value_after("--join") {
+        let address = match address {
+            Some(address) if address.contains(':') => address.clone(),
+            Some(address) => format!("{}:{}", address, DEFAULT_PORT),
+            None => format!("127.0.0.1:{}", DEFAULT_PORT),
+        };
+        println!("Connecting to {}...", address);
+        NetPeer::join(&address)
+    } else {
+        return None;
+    };
 
+    match result {
+        Ok(online) => Some(online),
+        Err(e) => {
+            eprintln!("Could not start the online match: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{grid_to_str, Board, Cell},
+    constants::{COLUMNS, ROWS},
+};
+
+pub const DEFAULT_PORT: u16 = 7878;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(120);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_LINE_LEN: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetMessage {
+    Start { seed: u64, round: u32 },
+    Rematch { round: u32 },
+    Attack { round: u32, lines: usize },
+    Meter { round: u32, lines: usize },
+    Field { round: u32, field: String },
+    ToppedOut { round: u32 },
+}
+
+impl NetMessage {
+    pub fn round(&self) -> u32 {
+        match *self {
+            Self::Start { round, .. }
+            | Self::Rematch { round }
+            | Self::Attack { round, .. }
+            | Self::Meter { round, .. }
+            | Self::Field { round, .. }
+            | Self::ToppedOut { round } => round,
+        }
+    }
+
+    fn clamped(self) -> Self {
+        match self {
+            Self::Attack { round, lines } => Self::Attack {
+                round,
+                lines: lines.min(ROWS as usize),
+            },
+            Self::Meter { round, lines } => Self::Meter {
+                round,
+                lines: lines.min(ROWS as usize),
+            },
+            message => message,
+        }
+    }
+}
+
+pub struct NetPeer {
+    outgoing: Sender<String>,
+    messages: Receiver<NetMessage>,
+    is_host: bool,
+    closed: bool,
+}
+
+impl NetPeer {
+    pub fn host(port: u16) -> io::Result<(Self, u64)> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let stream = accept_with_timeout(&listener, ACCEPT_TIMEOUT)?;
+        let mut peer = Self::new(stream, true)?;
+
+        let seed = rand::random();
+        peer.send(&NetMessage::Start { seed, round: 0 })?;
+        Ok((peer, seed))
+    }
+
+    pub fn join(address: &str) -> io::Result<(Self, u64)> {
+        let stream = TcpStream::connect(address)?;
+        let peer = Self::new(stream, false)?;
+
+        match peer.messages.recv_timeout(HANDSHAKE_TIMEOUT) {
+            Ok(NetMessage::Start { seed, .. }) => Ok((peer, seed)),
+            Ok(message) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected message {:?}", message),
+            )),
+            Err(RecvTimeoutError::Timeout) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "host did not start the match",
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "host closed the connection",
+            )),
+        }
+    }
+
+    fn new(stream: TcpStream, is_host: bool) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let mut writer = stream.try_clone()?;
+        let (sender, messages) = mpsc::channel();
+        let (outgoing, lines) = mpsc::channel::<String>();
+
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                let read = reader
+                    .by_ref()
+                    .take(MAX_LINE_LEN as u64 + 1)
+                    .read_until(b'\n', &mut line);
+                match read {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) if line.last() != Some(&b'\n') => break,
+                    Ok(_) => (),
+                }
+                let Ok(message) = serde_json::from_slice::<NetMessage>(&line) else {
+                    continue;
+                };
+                if sender.send(message.clamped()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        thread::spawn(move || {
+            for line in lines {
+                if writer.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            let _ = writer.shutdown(Shutdown::Both);
+        });
+
+        Ok(Self {
+            outgoing,
+            messages,
+            is_host,
+            closed: false,
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.is_host
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn send(&mut self, message: &NetMessage) -> io::Result<()> {
+        let mut line = serde_json::to_string(message).map_err(io::Error::other)?;
+        line.push('\n');
+        self.outgoing.send(line).map_err(|_| {
+            self.closed = true;
+            io::Error::new(io::ErrorKind::BrokenPipe, "connection closed")
+        })
+    }
+
+    pub fn try_recv(&mut self) -> Option<NetMessage> {
+        match self.messages.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+}
+
+fn accept_with_timeout(listener: &TcpListener, timeout: Duration) -> io::Result<TcpStream> {
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "no opponent joined in time",
+                    ));
+                }
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+pub fn field_of(board: &Board) -> String {
+    let mut grid = *board.grid();
+    if let Some(piece) = board.active_piece {
+        for (x, y) in piece.cells() {
+            if (0..COLUMNS as isize).contains(&x) && (0..ROWS as isize).contains(&y) {
+                grid[y as usize][x as usize] = Some(Cell::Block(piece.block));
+            }
+        }
+    }
+    grid_to_str(&grid)
+}
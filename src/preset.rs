@@ -0,0 +1,335 @@
+use crate::{
+    blocks::Block,
+    board::{grid_from_str, grid_to_str, presets, Grid},
+    clear::Objective,
+    constants::ROWS,
+    fumen,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub title: String,
+    pub description: String,
+    pub grid: Grid,
+    pub pieces: Vec<Block>,
+    pub hold: Option<Block>,
+    pub gravity: Option<usize>,
+    pub objective: Option<Objective>,
+}
+
+impl Preset {
+    pub fn builtin() -> Vec<Self> {
+        let preset = |title: &str, description: &str, grid: Grid, piece: Block, objective| Self {
+            title: title.to_string(),
+            description: description.to_string(),
+            grid,
+            pieces: vec![piece],
+            hold: None,
+            gravity: None,
+            objective: Some(objective),
+        };
+        let spin = |block, lines| Objective::Spin { block, lines };
+
+        vec![
+            preset(
+                "T-Spin Double Setup",
+                "slide the T under the overhang and spin it in",
+                presets::t_spin_double(),
+                Block::T,
+                spin(Block::T, 2),
+            ),
+            preset(
+                "T-Spin Triple Setup",
+                "rest the T on the ledge and kick it down into the slot",
+                presets::t_spin_triple(),
+                Block::T,
+                spin(Block::T, 3),
+            ),
+            preset(
+                "Quad Clear Setup",
+                "send the I piece down the right well",
+                presets::quad_clear(),
+                Block::Line,
+                Objective::Quad,
+            ),
+            preset(
+                "L-Spin Setup",
+                "tuck the L under the ledge and rotate it into the gap",
+                presets::l_spin(),
+                Block::L,
+                spin(Block::L, 2),
+            ),
+            preset(
+                "J-Spin Setup",
+                "tuck the J under the ledge and rotate it into the gap",
+                presets::j_spin(),
+                Block::J,
+                spin(Block::J, 2),
+            ),
+            preset(
+                "S-Spin Setup",
+                "twist the S into the slot",
+                presets::s_spin(),
+                Block::S,
+                spin(Block::S, 2),
+            ),
+            preset(
+                "Z-Spin Setup",
+                "twist the Z into the slot",
+                presets::z_spin(),
+                Block::Z,
+                spin(Block::Z, 2),
+            ),
+        ]
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut title = String::new();
+        let mut description = String::new();
+        let mut grid = None;
+        let mut pieces = Vec::new();
+        let mut hold = None;
+        let mut gravity = None;
+        let mut objective = None;
+
+        let mut lines = s.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let error = |message: String| format!("line {}: {}", index + 1, message);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected key = value".to_string()))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "title" => title = unquote(value),
+                "description" => description = unquote(value),
+                "pieces" => {
+                    pieces = unquote(value)
+                        .chars()
+                        .filter(|c| c.is_alphabetic())
+                        .map(|c| {
+                            Block::from_letter(c.to_ascii_uppercase())
+                                .ok_or_else(|| error(format!("unknown piece '{}'", c)))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "hold" => {
+                    let letter = unquote(value)
+                        .chars()
+                        .next()
+                        .ok_or_else(|| error("missing hold piece".to_string()))?;
+                    hold = Some(
+                        Block::from_letter(letter.to_ascii_uppercase())
+                            .ok_or_else(|| error(format!("unknown piece '{}'", letter)))?,
+                    );
+                }
+                "gravity" => {
+                    let value = unquote(value);
+                    gravity = Some(
+                        value
+                            .parse()
+                            .map_err(|_| error(format!("invalid gravity '{}'", value)))?,
+                    );
+                }
+                "goal" => {
+                    let value = unquote(value);
+                    objective = Some(
+                        Objective::parse(&value)
+                            .ok_or_else(|| error(format!("unknown goal '{}'", value)))?,
+                    );
+                }
+                "grid" => {
+                    let delimiter = ["\"\"\"", "'''"]
+                        .into_iter()
+                        .find(|delimiter| value.starts_with(delimiter));
+                    let text = match delimiter {
+                        Some(delimiter) => {
+                            let mut rows = Vec::new();
+                            let mut row = &value[delimiter.len()..];
+                            loop {
+                                if let Some(last) = row.trim_end().strip_suffix(delimiter) {
+                                    rows.push(last);
+                                    break;
+                                }
+                                rows.push(row);
+                                row = lines
+                                    .next()
+                                    .ok_or_else(|| error("unterminated grid".to_string()))?
+                                    .1;
+                            }
+                            rows.join("\n")
+                        }
+                        None if value.is_empty() => lines
+                            .by_ref()
+                            .map(|(_, l)| l)
+                            .take_while(|l| !l.trim().is_empty())
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        None => unquote(value),
+                    };
+                    grid = Some(parse_grid(&text).map_err(error)?);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            title,
+            description,
+            grid: grid.ok_or("missing grid")?,
+            pieces,
+            hold,
+            gravity,
+            objective,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if !self.title.is_empty() {
+            text.push_str(&format!("title = {}\n", quote(&self.title)));
+        }
+        if !self.description.is_empty() {
+            text.push_str(&format!("description = {}\n", quote(&self.description)));
+        }
+        if !self.pieces.is_empty() {
+            let pieces: String = self.pieces.iter().map(|block| block.letter()).collect();
+            text.push_str(&format!("pieces = \"{}\"\n", pieces));
+        }
+        if let Some(block) = self.hold {
+            text.push_str(&format!("hold = \"{}\"\n", block.letter()));
+        }
+        if let Some(gravity) = self.gravity {
+            text.push_str(&format!("gravity = {}\n", gravity));
+        }
+        if let Some(objective) = self.objective {
+            text.push_str(&format!("goal = \"{}\"\n", objective.name()));
+        }
+        text.push_str(&format!(
+            "grid = \"\"\"\n{}\n\"\"\"\n",
+            grid_to_str(&self.grid)
+        ));
+        text
+    }
+}
+
+fn parse_grid(text: &str) -> Result<Grid, String> {
+    if text.contains("115@") {
+        return fumen::decode(text)
+            .and_then(|pages| pages.first().map(|page| page.field))
+            .ok_or_else(|| "invalid fumen".to_string());
+    }
+
+    let rows = text.lines().filter(|l| !l.trim().is_empty()).count();
+    if rows > ROWS as usize {
+        return Err(format!("grid is taller than {} rows", ROWS));
+    }
+
+    Ok(grid_from_str(text))
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut unquoted = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unquoted.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => unquoted.push('\n'),
+                Some(escaped) => unquoted.push(escaped),
+                None => unquoted.push(c),
+            }
+        }
+        return unquoted;
+    }
+
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ai::reachable_placements,
+        board::{Board, Cell},
+    };
+
+    #[test]
+    fn parses_grid_closed_on_last_row() {
+        let preset =
+            Preset::parse("pieces = \"T\"\ngrid = \"\"\"\nXXXX..XXXX\nXXX...XXXX\"\"\"\n").unwrap();
+        let bottom = ROWS as usize - 1;
+        assert_eq!(preset.grid[bottom][0], Some(Cell::Garbage));
+        assert_eq!(preset.grid[bottom][3], None);
+        assert_eq!(preset.grid[bottom - 1][3], Some(Cell::Garbage));
+        assert_eq!(preset.grid[bottom - 2][0], None);
+        assert_eq!(preset.pieces, vec![Block::T]);
+    }
+
+    #[test]
+    fn rejects_tall_grid() {
+        let rows = vec!["XXXX.XXXXX"; ROWS as usize + 1].join("\n");
+        let text = format!("grid = \"\"\"\n{}\n\"\"\"\n", rows);
+        assert!(Preset::parse(&text).is_err());
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(Preset::parse("title = \"no grid\"\n").is_err());
+        assert!(Preset::parse("pieces = \"TQ\"\ngrid = \"XXXX.XXXXX\"\n").is_err());
+        assert!(Preset::parse("grid = \"\"\"\nXXXX.XXXXX\n").is_err());
+        assert!(Preset::parse("grid\n").is_err());
+    }
+
+    #[test]
+    fn round_trips_quoted_text() {
+        let mut preset = Preset::builtin().remove(0);
+        preset.title = "the \"hard\" one".to_string();
+        preset.description = "back\\slash".to_string();
+        assert_eq!(Preset::parse(&preset.to_text()), Ok(preset));
+    }
+
+    #[test]
+    fn builtin_objectives_are_reachable() {
+        for preset in Preset::builtin() {
+            let objective = preset.objective.unwrap();
+            let mut board = Board::new();
+            board.new_with_grid(preset.grid);
+            board.spawn_next_block(&preset.pieces[0]);
+
+            let is_reachable = reachable_placements(&board).into_iter().any(|(piece, _)| {
+                let mut sim = board.clone();
+                sim.active_piece = Some(piece);
+                sim.lock_current_block();
+                sim.last_clear.is_some_and(|clear| objective.is_met(&clear))
+            });
+            assert!(is_reachable, "{}", preset.title);
+        }
+    }
+}
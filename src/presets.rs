@@ -0,0 +1,82 @@
+#[cfg(not(feature = "vhs"))]
+use crate::scores::HighScores;
+use std::io;
+#[cfg(not(feature = "vhs"))]
+use std::{fs, path::PathBuf};
+
+use tetrus::preset::Preset;
+
+#[cfg(not(feature = "vhs"))]
+pub fn storage_dir() -> PathBuf {
+    HighScores::storage_path().with_file_name("presets")
+}
+
+#[cfg(not(feature = "vhs"))]
+pub fn load() -> (Vec<Preset>, Vec<String>) {
+    let mut presets = Preset::builtin();
+    let mut errors = Vec::new();
+    let Ok(entries) = fs::read_dir(storage_dir()) else {
+        return (presets, errors);
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("txt" | "toml")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Preset::parse(&content));
+        let mut preset = match parsed {
+            Ok(preset) => preset,
+            Err(error) => {
+                errors.push(format!("{}: {}", name, error));
+                continue;
+            }
+        };
+
+        if preset.title.is_empty() {
+            preset.title = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        presets.push(preset);
+    }
+
+    (presets, errors)
+}
+
+#[cfg(feature = "vhs")]
+pub fn load() -> (Vec<Preset>, Vec<String>) {
+    (Preset::builtin(), Vec::new())
+}
+
+#[cfg(not(feature = "vhs"))]
+pub fn save(preset: &Preset) -> io::Result<String> {
+    let dir = storage_dir();
+    fs::create_dir_all(&dir)?;
+
+    let name = (1..)
+        .map(|i| format!("setup-{}.txt", i))
+        .find(|name| !dir.join(name).exists())
+        .unwrap_or_default();
+    fs::write(dir.join(&name), preset.to_text())?;
+    Ok(name)
+}
+
+#[cfg(feature = "vhs")]
+pub fn save(_preset: &Preset) -> io::Result<String> {
+    Ok("setup-1.txt".to_string())
+}
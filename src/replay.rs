@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{action::GameAction, board::GameGoal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayInput {
+    pub frame: u64,
+    pub action: GameAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub goal: GameGoal,
+    pub frames: u64,
+    pub inputs: Vec<ReplayInput>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, goal: GameGoal) -> Self {
+        Self {
+            seed,
+            goal,
+            ..Default::default()
+        }
+    }
+
+    pub fn record(&mut self, frame: u64, action: GameAction) {
+        self.inputs.push(ReplayInput { frame, action });
+    }
+
+    pub fn finish(&mut self, frames: u64) {
+        self.frames = frames;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    next_input: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_input: 0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn next_action(&mut self, frame: u64) -> Option<GameAction> {
+        let input = self.replay.inputs.get(self.next_input)?;
+        if input.frame > frame {
+            return None;
+        }
+
+        self.next_input += 1;
+        Some(input.action)
+    }
+
+    pub fn is_finished(&self, frame: u64) -> bool {
+        self.next_input >= self.replay.inputs.len() && frame >= self.replay.frames
+    }
+}
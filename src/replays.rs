@@ -0,0 +1,54 @@
+#[cfg(not(feature = "vhs"))]
+use crate::scores::HighScores;
+use std::io;
+#[cfg(not(feature = "vhs"))]
+use std::{
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tetrus::replay::Replay;
+
+#[cfg(not(feature = "vhs"))]
+pub fn storage_dir() -> PathBuf {
+    HighScores::storage_path().with_file_name("replays")
+}
+
+#[cfg(not(feature = "vhs"))]
+pub fn load() -> Option<Replay> {
+    let path = fs::read_dir(storage_dir())
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .max()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(feature = "vhs")]
+pub fn load() -> Option<Replay> {
+    None
+}
+
+#[cfg(not(feature = "vhs"))]
+pub fn save(replay: &Replay) -> io::Result<()> {
+    if replay.inputs.is_empty() {
+        return Ok(());
+    }
+
+    let dir = storage_dir();
+    fs::create_dir_all(&dir)?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(io::Error::other)?
+        .as_millis();
+    let json =
+        serde_json::to_string(replay).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(dir.join(format!("{:020}.json", millis)), json)
+}
+
+#[cfg(feature = "vhs")]
+pub fn save(_replay: &Replay) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+#[cfg(not(feature = "vhs"))]
+use std::{fs, path::PathBuf};
+
+#[cfg(not(feature = "vhs"))]
+use crate::scores::HighScores;
+use tetrus::{blocks_manager::BlocksManager, board::Board, replay::Replay};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedMode {
+    Endless,
+    Marathon { start_level: usize },
+    Master,
+    Sprint,
+    Blitz,
+    Dig { rows: usize, messiness: u32 },
+    Survival { accelerating: bool },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub mode: SavedMode,
+    pub board: Board,
+    pub blocks_manager: BlocksManager,
+    pub replay: Replay,
+}
+
+impl SavedGame {
+    #[cfg(not(feature = "vhs"))]
+    pub fn storage_path() -> PathBuf {
+        HighScores::storage_path().with_file_name("saved_game.json")
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn exists() -> bool {
+        Self::storage_path().is_file()
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn exists() -> bool {
+        false
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn load() -> io::Result<Self> {
+        let content = fs::read_to_string(Self::storage_path())?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn load() -> io::Result<Self> {
+        Err(io::ErrorKind::NotFound.into())
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::storage_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn save(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn delete() -> io::Result<()> {
+        match fs::remove_file(Self::storage_path()) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn delete() -> io::Result<()> {
+        Ok(())
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn back_up() -> io::Result<PathBuf> {
+        let path = Self::storage_path();
+        let backup = path.with_extension("json.bak");
+        fs::rename(path, &backup)?;
+        Ok(backup)
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn back_up() -> io::Result<std::path::PathBuf> {
+        Ok("saved_game.json.bak".into())
+    }
+}
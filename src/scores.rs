@@ -1,7 +1,9 @@
+#[cfg(not(feature = "vhs"))]
+use crate::utils::storage::data_dir;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::{collections::HashMap, fmt, io};
+use std::{cmp::Ordering, collections::HashMap, fmt, io};
 #[cfg(not(feature = "vhs"))]
-use std::{env, fs, path::Path, path::PathBuf};
+use std::{fs, path::PathBuf};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Initials {
@@ -100,6 +102,59 @@ pub struct ScoreEntry {
     pub score: usize,
     pub lines: usize,
     pub level: usize,
+    #[serde(default)]
+    pub time_ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Leaderboard {
+    Endless,
+    Sprint,
+    Blitz,
+    Dig { rows: usize, messiness: u32 },
+    Survival,
+    SurvivalRising,
+    Marathon(usize),
+    Master,
+}
+
+impl Leaderboard {
+    pub fn key(self) -> String {
+        match self {
+            Self::Endless => "endless".to_string(),
+            Self::Sprint => "sprint".to_string(),
+            Self::Blitz => "blitz".to_string(),
+            Self::Dig { rows, messiness } => format!("dig{}_{}", rows, messiness),
+            Self::Survival => "survival".to_string(),
+            Self::SurvivalRising => "survival_rising".to_string(),
+            Self::Marathon(start_level) => format!("marathon_{}", start_level),
+            Self::Master => "master".to_string(),
+        }
+    }
+
+    fn compare(self, a: &ScoreEntry, b: &ScoreEntry) -> Ordering {
+        match self {
+            Self::Endless | Self::Blitz | Self::Marathon(_) => b.score.cmp(&a.score),
+            Self::Sprint | Self::Dig { .. } => a.time_ms.cmp(&b.time_ms),
+            Self::Survival | Self::SurvivalRising => b
+                .time_ms
+                .cmp(&a.time_ms)
+                .then_with(|| b.lines.cmp(&a.lines)),
+            Self::Master => b
+                .lines
+                .cmp(&a.lines)
+                .then_with(|| a.time_ms.cmp(&b.time_ms)),
+        }
+    }
+
+    fn is_rankable(self, entry: &ScoreEntry) -> bool {
+        match self {
+            Self::Endless | Self::Blitz | Self::Marathon(_) => entry.score > 0,
+            Self::Sprint | Self::Dig { .. } => entry.time_ms > 0,
+            Self::Survival | Self::SurvivalRising => entry.time_ms > 0,
+            Self::Master => entry.lines > 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -112,19 +167,7 @@ impl HighScores {
 
     #[cfg(not(feature = "vhs"))]
     pub fn storage_path() -> PathBuf {
-        if let Ok(appdata) = env::var("APPDATA") {
-            Path::new(&appdata).join("tetrus").join("scores.json")
-        } else if let Ok(xdg_data) = env::var("XDG_DATA_HOME") {
-            Path::new(&xdg_data).join("tetrus").join("scores.json")
-        } else if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
-            Path::new(&home)
-                .join(".local")
-                .join("share")
-                .join("tetrus")
-                .join("scores.json")
-        } else {
-            PathBuf::from("scores.json")
-        }
+        data_dir().join("scores.json")
     }
 
     #[cfg(not(feature = "vhs"))]
@@ -153,24 +196,28 @@ impl HighScores {
                     score: 4_300,
                     lines: 48,
                     level: 5,
+                    time_ms: 0,
                 },
                 ScoreEntry {
                     initials: Initials::from_str("DOREMY"),
                     score: 3_120,
                     lines: 32,
                     level: 4,
+                    time_ms: 0,
                 },
                 ScoreEntry {
                     initials: Initials::from_str("PROZ"),
                     score: 1_000,
                     lines: 22,
                     level: 3,
+                    time_ms: 0,
                 },
                 ScoreEntry {
                     initials: Initials::from_str("GARBO"),
                     score: 200,
                     lines: 15,
                     level: 2,
+                    time_ms: 0,
                 },
             ],
         );
@@ -193,22 +240,30 @@ impl HighScores {
         Ok(())
     }
 
-    pub fn check_qualification(&self, mode: &str, score: usize) -> Option<usize> {
-        if score == 0 {
+    pub fn check_qualification(
+        &self,
+        leaderboard: Leaderboard,
+        entry: &ScoreEntry,
+    ) -> Option<usize> {
+        if !leaderboard.is_rankable(entry) {
             return None;
         }
 
-        let entries = self.modes.get(mode);
+        let entries = self.modes.get(&leaderboard.key());
         match entries {
             None => Some(1),
             Some(list) => {
-                if list.is_empty() {
-                    Some(1)
-                } else if list.len() < Self::TOP_LIMIT {
-                    let rank = list.iter().filter(|e| e.score > score).count() + 1;
-                    Some(rank)
-                } else if score > list.last().map(|e| e.score).unwrap_or(0) {
-                    let rank = list.iter().filter(|e| e.score > score).count() + 1;
+                let rank = list
+                    .iter()
+                    .filter(|e| leaderboard.compare(e, entry) == Ordering::Less)
+                    .count()
+                    + 1;
+
+                let beats_last = list
+                    .last()
+                    .is_none_or(|last| leaderboard.compare(entry, last) == Ordering::Less);
+
+                if list.len() < Self::TOP_LIMIT || beats_last {
                     Some(rank)
                 } else {
                     None
@@ -217,11 +272,11 @@ impl HighScores {
         }
     }
 
-    pub fn insert(&mut self, mode: &str, entry: ScoreEntry) -> usize {
-        let entries = self.modes.entry(mode.to_string()).or_default();
+    pub fn insert(&mut self, leaderboard: Leaderboard, entry: ScoreEntry) -> usize {
+        let entries = self.modes.entry(leaderboard.key()).or_default();
         let target_entry = entry.clone();
         entries.push(entry);
-        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        entries.sort_by(|a, b| leaderboard.compare(a, b));
 
         let rank = entries
             .iter()
@@ -234,7 +289,10 @@ impl HighScores {
         rank
     }
 
-    pub fn get_top_5(&self, mode: &str) -> &[ScoreEntry] {
-        self.modes.get(mode).map(|v| v.as_slice()).unwrap_or(&[])
+    pub fn get_top_5(&self, leaderboard: Leaderboard) -> &[ScoreEntry] {
+        self.modes
+            .get(&leaderboard.key())
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
     }
 }
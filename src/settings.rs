@@ -0,0 +1,83 @@
+#[cfg(not(feature = "vhs"))]
+use crate::utils::storage::data_dir;
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "vhs"))]
+use std::{fs, path::PathBuf};
+use std::{io, time::Duration};
+
+use tetrus::constants::FRAME_DURATION;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handling {
+    pub das_frames: u32,
+    pub arr_frames: u32,
+    pub soft_drop_factor: u32,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das_frames: 10,
+            arr_frames: 2,
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+impl Handling {
+    pub const MAX_DAS_FRAMES: u32 = 20;
+    pub const MAX_ARR_FRAMES: u32 = 5;
+    pub const MAX_SOFT_DROP_FACTOR: u32 = 40;
+
+    pub fn das(&self) -> Duration {
+        FRAME_DURATION * self.das_frames
+    }
+
+    pub fn arr(&self) -> Duration {
+        FRAME_DURATION * self.arr_frames
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub handling: Handling,
+}
+
+impl Settings {
+    #[cfg(not(feature = "vhs"))]
+    pub fn storage_path() -> PathBuf {
+        data_dir().join("settings.json")
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn load() -> Self {
+        let path = Self::storage_path();
+        if let Ok(content) = fs::read_to_string(&path) {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn load() -> Self {
+        Self::default()
+    }
+
+    #[cfg(not(feature = "vhs"))]
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::storage_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    #[cfg(feature = "vhs")]
+    pub fn save(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+use std::{
+    cmp::Reverse,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::GameAction,
+    ai::{self, Ai},
+    blocks::{Block, Rotation},
+    blocks_manager::BlocksManager,
+    board::{ActivePiece, Board, Cell},
+    constants::{COLUMNS, ROWS},
+};
+
+const BOARD_HEIGHT: usize = 40;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const SUGGESTION_SEARCH_LIMIT: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Randomizer {
+    SevenBag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Orientation {
+    const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    fn of(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::Deg0 => Self::North,
+            Rotation::Deg90 => Self::East,
+            Rotation::Deg180 => Self::South,
+            Rotation::Deg270 => Self::West,
+        }
+    }
+
+    fn rotate(self, (x, y): (isize, isize)) -> (isize, isize) {
+        match self {
+            Self::North => (x, y),
+            Self::East => (y, -x),
+            Self::South => (-x, -y),
+            Self::West => (-y, x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveSpin {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    #[serde(rename = "type")]
+    pub piece: char,
+    pub orientation: Orientation,
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    pub location: Location,
+    #[serde(default)]
+    pub spin: MoveSpin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Start {
+    pub hold: Option<char>,
+    pub queue: Vec<char>,
+    pub combo: usize,
+    pub back_to_back: bool,
+    pub board: Vec<[Option<char>; COLUMNS as usize]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FrontendMessage {
+    Rules {
+        randomizer: Randomizer,
+    },
+    Start(Start),
+    Stop,
+    Suggest,
+    Play {
+        #[serde(rename = "move")]
+        mv: Move,
+    },
+    NewPiece {
+        piece: char,
+    },
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotMessage {
+    Info {
+        name: String,
+        version: String,
+        author: String,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+    Ready,
+    Error {
+        reason: String,
+    },
+    Suggestion {
+        moves: Vec<Move>,
+    },
+}
+
+fn north_offsets(block: Block) -> [(isize, isize); 4] {
+    match block {
+        Block::Line => [(-1, 0), (0, 0), (1, 0), (2, 0)],
+        Block::Square => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        Block::T => [(-1, 0), (0, 0), (1, 0), (0, 1)],
+        Block::L => [(-1, 0), (0, 0), (1, 0), (1, 1)],
+        Block::J => [(-1, 0), (0, 0), (1, 0), (-1, 1)],
+        Block::S => [(-1, 0), (0, 0), (0, 1), (1, 1)],
+        Block::Z => [(-1, 1), (0, 1), (0, 0), (1, 0)],
+    }
+}
+
+fn flip_rows((x, y): (isize, isize)) -> (isize, isize) {
+    (x, ROWS as isize - 1 - y)
+}
+
+fn sorted(mut cells: [(isize, isize); 4]) -> [(isize, isize); 4] {
+    cells.sort_unstable();
+    cells
+}
+
+pub fn location_cells(location: &Location) -> Option<[(isize, isize); 4]> {
+    let block = Block::from_letter(location.piece)?;
+    Some(north_offsets(block).map(|offset| {
+        let (dx, dy) = location.orientation.rotate(offset);
+        flip_rows((location.x + dx, location.y + dy))
+    }))
+}
+
+pub fn location_of(piece: &ActivePiece) -> Location {
+    let cells = sorted(piece.cells().map(flip_rows));
+    let offsets = north_offsets(piece.block);
+    let actual = Orientation::of(piece.rotation);
+    let orientations =
+        std::iter::once(actual).chain(Orientation::ALL.into_iter().filter(|&o| o != actual));
+
+    for orientation in orientations {
+        let rotated = offsets.map(|offset| orientation.rotate(offset));
+        let (cx, cy) = cells[0];
+        for (dx, dy) in rotated {
+            let (x, y) = (cx - dx, cy - dy);
+            if sorted(rotated.map(|(ox, oy)| (x + ox, y + oy))) == cells {
+                return Location {
+                    piece: piece.block.letter(),
+                    orientation,
+                    x,
+                    y,
+                };
+            }
+        }
+    }
+
+    unreachable!("every piece has a matching orientation")
+}
+
+pub fn move_of(board: &Board, piece: &ActivePiece) -> Move {
+    let spin = match board.spin_of(piece) {
+        Some(spin) if spin.mini => MoveSpin::Mini,
+        Some(_) => MoveSpin::Full,
+        None => MoveSpin::None,
+    };
+
+    Move {
+        location: location_of(piece),
+        spin,
+    }
+}
+
+pub fn start_message(board: &Board, blocks_manager: &BlocksManager) -> Start {
+    let grid = board.grid();
+    let rows = (0..BOARD_HEIGHT)
+        .map(|row| {
+            let mut cells = [None; COLUMNS as usize];
+            if row < ROWS as usize {
+                for (x, cell) in grid[ROWS as usize - 1 - row].iter().enumerate() {
+                    cells[x] = cell.map(|cell| match cell {
+                        Cell::Block(block) => block.letter(),
+                        Cell::Garbage => 'G',
+                    });
+                }
+            }
+            cells
+        })
+        .collect();
+
+    let queue = board
+        .active_piece
+        .map(|piece| piece.block)
+        .into_iter()
+        .chain(blocks_manager.get_next_blocks())
+        .map(Block::letter)
+        .collect();
+
+    Start {
+        hold: board.hold_state.block.map(Block::letter),
+        queue,
+        combo: board.combo.count,
+        back_to_back: board.stats.b2b_count > 0,
+        board: rows,
+    }
+}
+
+pub fn actions_for_move(
+    board: &Board,
+    blocks_manager: &BlocksManager,
+    mv: &Move,
+) -> Option<Vec<GameAction>> {
+    let target = sorted(location_cells(&mv.location)?);
+    let block = Block::from_letter(mv.location.piece)?;
+    let active = board.active_piece?;
+
+    let mut sim = board.clone();
+    let mut actions = Vec::new();
+    if active.block != block {
+        let mut preview = blocks_manager.clone();
+        if !sim.hold_block(&mut preview) || sim.active_piece?.block != block {
+            return None;
+        }
+        actions.push(GameAction::Hold);
+    }
+
+    let wants_spin = mv.spin != MoveSpin::None;
+    let (_, path) = ai::reachable_placements(&sim)
+        .into_iter()
+        .filter(|(piece, _)| sorted(piece.cells()) == target)
+        .max_by_key(|(piece, path)| {
+            (
+                piece.last_action_was_rotation == wants_spin,
+                Orientation::of(piece.rotation) == mv.location.orientation,
+                Reverse(path.len()),
+            )
+        })?;
+
+    actions.extend(path);
+    actions.push(GameAction::HardDrop);
+    Some(actions)
+}
+
+pub struct TbpBot {
+    pub name: String,
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<BotMessage>,
+}
+
+impl TbpBot {
+    pub fn spawn(command: &[String]) -> io::Result<Self> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing bot command"))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("bot stdin is piped");
+        let stdout = child.stdout.take().expect("bot stdout is piped");
+
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Ok(message) = serde_json::from_str(&line) {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut bot = Self {
+            name: String::new(),
+            child,
+            stdin,
+            messages,
+        };
+
+        match bot.wait_message()? {
+            BotMessage::Info { name, .. } => bot.name = name,
+            message => return Err(unexpected(message)),
+        }
+
+        bot.send(&FrontendMessage::Rules {
+            randomizer: Randomizer::SevenBag,
+        })?;
+
+        match bot.wait_message()? {
+            BotMessage::Ready => Ok(bot),
+            message => Err(unexpected(message)),
+        }
+    }
+
+    pub fn send(&mut self, message: &FrontendMessage) -> io::Result<()> {
+        let json = serde_json::to_string(message)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(self.stdin, "{}", json)?;
+        self.stdin.flush()
+    }
+
+    pub fn try_recv(&self) -> io::Result<Option<BotMessage>> {
+        match self.messages.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => {
+                Err(io::Error::new(io::ErrorKind::UnexpectedEof, "bot exited"))
+            }
+        }
+    }
+
+    fn wait_message(&self) -> io::Result<BotMessage> {
+        self.messages
+            .recv_timeout(HANDSHAKE_TIMEOUT)
+            .map_err(|e| match e {
+                RecvTimeoutError::Timeout => {
+                    io::Error::new(io::ErrorKind::TimedOut, "bot did not answer")
+                }
+                RecvTimeoutError::Disconnected => {
+                    io::Error::new(io::ErrorKind::UnexpectedEof, "bot exited")
+                }
+            })
+    }
+}
+
+impl Drop for TbpBot {
+    fn drop(&mut self) {
+        let _ = self.send(&FrontendMessage::Quit);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn unexpected(message: BotMessage) -> io::Error {
+    let reason = match message {
+        BotMessage::Error { reason } => reason,
+        message => format!("unexpected message {:?}", message),
+    };
+    io::Error::other(reason)
+}
+
+pub struct TbpSession {
+    bot: TbpBot,
+    fallback: Ai,
+    running: bool,
+    needs_start: bool,
+    suggested_for: Option<u64>,
+    stale_suggestions: usize,
+    unrevealed_pieces: usize,
+}
+
+impl TbpSession {
+    pub fn new(bot: TbpBot) -> Self {
+        Self {
+            bot,
+            fallback: Ai::default(),
+            running: false,
+            needs_start: true,
+            suggested_for: None,
+            stale_suggestions: 0,
+            unrevealed_pieces: 0,
+        }
+    }
+
+    pub fn bot_name(&self) -> &str {
+        &self.bot.name
+    }
+
+    pub fn restart(&mut self) -> io::Result<()> {
+        if self.running {
+            self.bot.send(&FrontendMessage::Stop)?;
+            self.running = false;
+        }
+        self.needs_start = true;
+        if self.suggested_for.take().is_some() {
+            self.stale_suggestions += 1;
+        }
+        self.unrevealed_pieces = 0;
+        Ok(())
+    }
+
+    pub fn poll(
+        &mut self,
+        board: &Board,
+        blocks_manager: &BlocksManager,
+    ) -> io::Result<Option<Vec<GameAction>>> {
+        if !board.is_block_falling() || board.is_paused() {
+            return Ok(None);
+        }
+
+        let piece = blocks_manager.dealt();
+        if self
+            .suggested_for
+            .is_some_and(|suggested| suggested != piece)
+        {
+            self.restart()?;
+        }
+
+        if self.needs_start {
+            self.bot.send(&FrontendMessage::Start(start_message(
+                board,
+                blocks_manager,
+            )))?;
+            self.needs_start = false;
+            self.running = true;
+        }
+
+        if self.suggested_for.is_none() {
+            let preview = blocks_manager.get_next_blocks();
+            let revealed = self.unrevealed_pieces.min(preview.len());
+            for block in &preview[preview.len() - revealed..] {
+                self.bot.send(&FrontendMessage::NewPiece {
+                    piece: block.letter(),
+                })?;
+            }
+            self.unrevealed_pieces = 0;
+
+            self.bot.send(&FrontendMessage::Suggest)?;
+            self.suggested_for = Some(piece);
+            return Ok(None);
+        }
+
+        let moves = loop {
+            match self.bot.try_recv()? {
+                None => return Ok(None),
+                Some(BotMessage::Error { reason }) => return Err(io::Error::other(reason)),
+                Some(BotMessage::Suggestion { .. }) if self.stale_suggestions > 0 => {
+                    self.stale_suggestions -= 1;
+                }
+                Some(BotMessage::Suggestion { moves }) => break moves,
+                Some(_) => (),
+            }
+        };
+        self.suggested_for = None;
+
+        let chosen = moves.iter().take(SUGGESTION_SEARCH_LIMIT).find_map(|mv| {
+            actions_for_move(board, blocks_manager, mv).map(|actions| (*mv, actions))
+        });
+
+        let (mv, actions) = match chosen {
+            Some(chosen) => chosen,
+            None => {
+                let Some(placement) = self.fallback.best_placement(board, blocks_manager) else {
+                    return Ok(None);
+                };
+                (move_of(board, &placement.piece), placement.actions)
+            }
+        };
+
+        let uses_hold = actions.first() == Some(&GameAction::Hold);
+        self.unrevealed_pieces = if uses_hold && board.hold_state.block.is_none() {
+            2
+        } else {
+            1
+        };
+
+        self.bot.send(&FrontendMessage::Play { mv })?;
+        Ok(Some(actions))
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::constants::FRAME_DURATION;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Timer {
+    frames: u64,
+    running: bool,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.running = false;
+    }
+
+    pub fn tick(&mut self) {
+        if self.running {
+            self.frames += 1;
+        }
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        frames_to_duration(self.frames)
+    }
+
+    pub fn reset(&mut self) {
+        self.frames = 0;
+        self.running = false;
+    }
+}
+
+pub fn frames_to_duration(frames: u64) -> Duration {
+    Duration::from_nanos((FRAME_DURATION.as_nanos() as u64).saturating_mul(frames))
+}
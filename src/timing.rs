@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use crate::{
+    board::GameGoal,
+    constants::{FRAME_DURATION, LOCK_DELAY_FRAMES_DURATION},
+};
+
+pub const GRAVITY_DENOMINATOR: u32 = 256;
+pub const INSTANT_GRAVITY: u32 = 20 * GRAVITY_DENOMINATOR;
+pub const MASTER_SECTION_LEVELS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub gravity: Option<u32>,
+    pub lock_delay: Duration,
+    pub spawn_delay: Duration,
+    pub line_clear_delay: Duration,
+}
+
+pub const STANDARD_TIMING: Timing = Timing {
+    gravity: None,
+    lock_delay: LOCK_DELAY_FRAMES_DURATION,
+    spawn_delay: Duration::ZERO,
+    line_clear_delay: Duration::ZERO,
+};
+
+pub const MASTER_TIMINGS: [Timing; 10] = [
+    master(64, 30, 25, 40),
+    master(256, 30, 25, 40),
+    master(1024, 30, 25, 40),
+    master(INSTANT_GRAVITY, 30, 25, 40),
+    master(INSTANT_GRAVITY, 30, 25, 25),
+    master(INSTANT_GRAVITY, 30, 25, 16),
+    master(INSTANT_GRAVITY, 30, 16, 12),
+    master(INSTANT_GRAVITY, 30, 12, 6),
+    master(INSTANT_GRAVITY, 17, 12, 6),
+    master(INSTANT_GRAVITY, 15, 6, 6),
+];
+
+const fn frames(count: u64) -> Duration {
+    Duration::from_nanos(FRAME_DURATION.as_nanos() as u64 * count)
+}
+
+const fn master(gravity: u32, lock_delay: u64, spawn_delay: u64, line_clear_delay: u64) -> Timing {
+    Timing {
+        gravity: Some(gravity),
+        lock_delay: frames(lock_delay),
+        spawn_delay: frames(spawn_delay),
+        line_clear_delay: frames(line_clear_delay),
+    }
+}
+
+impl Timing {
+    pub fn for_level(goal: GameGoal, level: usize) -> Self {
+        match goal {
+            GameGoal::Master => {
+                let section = level.saturating_sub(1) / MASTER_SECTION_LEVELS;
+                MASTER_TIMINGS[section.min(MASTER_TIMINGS.len() - 1)]
+            }
+            _ => STANDARD_TIMING,
+        }
+    }
+
+    pub fn is_instant(&self) -> bool {
+        self.gravity
+            .is_some_and(|gravity| gravity >= INSTANT_GRAVITY)
+    }
+
+    pub fn row_interval(&self) -> Option<Duration> {
+        self.gravity
+            .map(|gravity| FRAME_DURATION * GRAVITY_DENOMINATOR / gravity.max(1))
+    }
+}
@@ -1,9 +1,13 @@
+mod board_view;
 mod board_widget;
+mod editor_widget;
 mod held_block_widget;
+mod keymap;
 mod menu_widget;
 mod metrics_widget;
 mod movement_widget;
 mod next_blocks_widget;
+mod online_widget;
 
 #[cfg(debug_assertions)]
 mod debug_widget;
@@ -11,27 +15,52 @@ mod debug_widget;
 #[cfg(debug_assertions)]
 use crate::tui::debug_widget::DebugWidget;
 use crate::tui::{
+    board_view::GarbageMeterView,
     board_widget::{BoardState, BoardWidget},
     gameover_widget::{GameoverState, GameoverWidget},
     held_block_widget::HeldBlockWidget,
+    keymap::Keymap,
     menu_widget::{MenuState, MenuWidget},
     metrics_widget::MetricsWidget,
     movement_widget::MovementWidget,
     next_blocks_widget::NextBlocksWidget,
+    online_widget::{OnlineEvent, OnlineWidget, RoundEvent},
 };
 use ratatui::{
+    layout::Rect,
     macros::{constraint, horizontal, line, vertical},
     style::Stylize,
     text::Line,
     DefaultTerminal, Frame,
 };
 
-use crossterm::event::{poll, read};
-use std::{io, time::Duration};
+use crossterm::{
+    event::{
+        poll, read, KeyCode, KeyEvent, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::supports_keyboard_enhancement,
+};
+use std::{
+    io::{self, stdout},
+    time::Duration,
+};
+
+use tetrus::{
+    board::GameGoal,
+    constants::{BLITZ_DURATION, COLUMNS, MARATHON_LINES, ROWS, SPRINT_LINES},
+    fumen::{self, FUMEN_URL},
+    netplay::{NetMessage, NetPeer},
+    preset::Preset,
+    replay::Replay,
+    tbp::TbpSession,
+};
 
 use crate::{
     colors::ORANGE,
-    constants::{COLUMNS, ROWS},
+    fumens, replays,
+    saved_game::{SavedGame, SavedMode},
 };
 
 #[derive(PartialEq, Clone, Copy)]
@@ -41,26 +70,36 @@ pub enum GameState {
     GameOver,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum ActiveGameMode {
     Endless,
-    LearnMoves {
-        grid: crate::board::Grid,
-        starting_pieces: &'static [crate::blocks::Block],
-        gravity: usize,
-    },
+    Marathon { start_level: usize },
+    Master,
+    Sprint,
+    Blitz,
+    Dig { rows: usize, messiness: u32 },
+    Survival { accelerating: bool },
+    LearnMoves { preset: Box<Preset>, gravity: usize },
+    Replay { replay: Box<Replay>, speed: u32 },
+    Ai,
+    Bot,
+    Versus,
+    Online,
 }
 
 pub struct Game<'a> {
     title: Line<'a>,
     game_state: GameState,
     active_game_mode: ActiveGameMode,
+    last_replay: Option<Replay>,
 
     // TODO: use a state machine to not have every widget in memory at any time
     menu_widget: MenuWidget<'a>,
     metrics_widget: MetricsWidget,
     movement_widget: MovementWidget,
     board_widget: BoardWidget,
+    opponent_widget: BoardWidget,
+    online_widget: Option<OnlineWidget>,
     held_block_widget: HeldBlockWidget,
     next_blocks_widget: NextBlocksWidget,
     gameover_widget: GameoverWidget<'a>,
@@ -85,11 +124,14 @@ impl<'a> Game<'a> {
             title: title.clone(),
             game_state: GameState::Menu,
             active_game_mode: ActiveGameMode::Endless,
+            last_replay: None,
 
             menu_widget: MenuWidget::new(title),
             metrics_widget: MetricsWidget::new(),
             movement_widget: MovementWidget::new(),
             board_widget: BoardWidget::new(),
+            opponent_widget: BoardWidget::new(),
+            online_widget: None,
             held_block_widget: HeldBlockWidget::new(),
             next_blocks_widget: NextBlocksWidget::new(),
             gameover_widget: GameoverWidget::new(),
@@ -98,56 +140,200 @@ impl<'a> Game<'a> {
         }
     }
 
+    pub fn watch_bot(&mut self, bot: TbpSession) {
+        self.board_widget.set_bot(bot);
+        self.board_widget.watch_bot();
+        self.active_game_mode = ActiveGameMode::Bot;
+        self.game_state = GameState::Game;
+    }
+
+    pub fn play_online(&mut self, peer: NetPeer, seed: u64) {
+        self.online_widget = Some(OnlineWidget::new(peer));
+        self.start_online_round(seed, 0);
+    }
+
     fn handle_events(&mut self) -> io::Result<bool> {
         while poll(Duration::ZERO)? {
-            if let Some(event) = read().map_or(None, |e| e.as_key_press_event()) {
+            if let Some(event) = read().map_or(None, |e| e.as_key_event()) {
+                if event.is_release() && self.game_state != GameState::Game {
+                    continue;
+                }
+
                 match self.game_state {
-                    GameState::Menu => match self.menu_widget.handle_key_event(event) {
-                        MenuState::Brake => return Ok(true),
-                        MenuState::EnterGame => {
-                            self.active_game_mode = ActiveGameMode::Endless;
-                            self.game_state = GameState::Game;
-                            self.board_widget.new_game();
+                    GameState::Menu => {
+                        let menu_state = self.menu_widget.handle_key_event(event);
+                        self.board_widget.handling = self.menu_widget.settings.handling;
+                        self.opponent_widget.handling = self.menu_widget.settings.handling;
+                        self.board_widget.keymap = Keymap::DEFAULT;
+
+                        match menu_state {
+                            MenuState::Brake => return Ok(true),
+                            MenuState::EnterGame => {
+                                self.active_game_mode = ActiveGameMode::Endless;
+                                self.game_state = GameState::Game;
+                                self.board_widget.new_game();
+                            }
+                            MenuState::EnterMarathon(start_level) => {
+                                self.active_game_mode = ActiveGameMode::Marathon { start_level };
+                                self.game_state = GameState::Game;
+                                self.board_widget.new_game_with_goal(GameGoal::Marathon {
+                                    lines: MARATHON_LINES,
+                                    start_level,
+                                });
+                            }
+                            MenuState::EnterMaster => {
+                                self.active_game_mode = ActiveGameMode::Master;
+                                self.game_state = GameState::Game;
+                                self.board_widget.new_game_with_goal(GameGoal::Master);
+                            }
+                            MenuState::EnterSprint => {
+                                self.active_game_mode = ActiveGameMode::Sprint;
+                                self.game_state = GameState::Game;
+                                self.board_widget
+                                    .new_game_with_goal(GameGoal::Lines(SPRINT_LINES));
+                            }
+                            MenuState::EnterBlitz => {
+                                self.active_game_mode = ActiveGameMode::Blitz;
+                                self.game_state = GameState::Game;
+                                self.board_widget
+                                    .new_game_with_goal(GameGoal::Time(BLITZ_DURATION));
+                            }
+                            MenuState::EnterDig(rows, messiness) => {
+                                self.active_game_mode = ActiveGameMode::Dig { rows, messiness };
+                                self.game_state = GameState::Game;
+                                self.board_widget
+                                    .new_game_with_goal(GameGoal::Dig { rows, messiness });
+                            }
+                            MenuState::EnterSurvival(accelerating) => {
+                                self.active_game_mode = ActiveGameMode::Survival { accelerating };
+                                self.game_state = GameState::Game;
+                                self.board_widget
+                                    .new_game_with_goal(GameGoal::Survival { accelerating });
+                            }
+                            MenuState::EnterVersus => {
+                                self.active_game_mode = ActiveGameMode::Versus;
+                                self.game_state = GameState::Game;
+                                self.board_widget.keymap = Keymap::PLAYER_ONE;
+                                self.opponent_widget.keymap = Keymap::PLAYER_TWO;
+                                self.new_versus_game();
+                            }
+                            MenuState::EnterGameWithPreset(preset, gravity) => {
+                                self.board_widget
+                                    .new_game_with_preset((*preset).clone(), gravity);
+                                self.active_game_mode =
+                                    ActiveGameMode::LearnMoves { preset, gravity };
+                                self.game_state = GameState::Game;
+                            }
+                            MenuState::WatchReplay(replay, speed) => {
+                                self.board_widget.watch_replay((*replay).clone(), speed);
+                                self.active_game_mode = ActiveGameMode::Replay { replay, speed };
+                                self.game_state = GameState::Game;
+                            }
+                            MenuState::WatchAi => {
+                                self.board_widget.watch_ai();
+                                self.active_game_mode = ActiveGameMode::Ai;
+                                self.game_state = GameState::Game;
+                            }
+                            MenuState::Continue => self.continue_saved_game(),
+                            MenuState::Pass => (),
                         }
-                        MenuState::EnterGameWithPreset(grid, pieces, gravity) => {
-                            self.active_game_mode = ActiveGameMode::LearnMoves {
-                                grid,
-                                starting_pieces: pieces,
-                                gravity,
-                            };
-                            self.game_state = GameState::Game;
-                            self.board_widget
-                                .new_game_with_preset(grid, pieces, gravity);
+                    }
+                    GameState::Game if matches!(self.active_game_mode, ActiveGameMode::Versus) => {
+                        if self.handle_versus_key_event(event) {
+                            return Ok(true);
                         }
-                        MenuState::Pass => (),
-                    },
+                    }
+                    GameState::Game if matches!(self.active_game_mode, ActiveGameMode::Online) => {
+                        if self.handle_online_key_event(event) {
+                            return Ok(true);
+                        }
+                    }
                     GameState::Game => match self.board_widget.handle_key_event(event) {
-                        BoardState::Brake => return Ok(true),
+                        BoardState::Brake => {
+                            self.save_game();
+                            return Ok(true);
+                        }
+                        BoardState::ExportFumen => {
+                            let board = &self.board_widget.board;
+                            let data =
+                                fumen::encode_grid(board.grid(), board.active_piece.as_ref());
+                            if fumens::save(&format!("{}{}", FUMEN_URL, data)).is_ok() {
+                                self.board_widget.set_fumen_exported();
+                            }
+                        }
+                        BoardState::Save => {
+                            self.save_game();
+                            self.menu_widget.set_has_saved_game(SavedGame::exists());
+                            self.game_state = GameState::Menu;
+                        }
                         _ => (),
                     },
                     GameState::GameOver => match self.gameover_widget.handle_key_event(event) {
                         GameoverState::Brake => return Ok(true),
+                        GameoverState::EnterGame
+                            if matches!(self.active_game_mode, ActiveGameMode::Online) =>
+                        {
+                            self.request_rematch();
+                        }
                         GameoverState::EnterGame => {
                             self.game_state = GameState::Game;
-                            match self.active_game_mode {
+                            match &self.active_game_mode {
                                 ActiveGameMode::Endless => {
                                     self.board_widget.new_game();
                                 }
-                                ActiveGameMode::LearnMoves {
-                                    grid,
-                                    starting_pieces,
-                                    gravity,
-                                } => {
-                                    self.board_widget.new_game_with_preset(
-                                        grid,
-                                        starting_pieces,
-                                        gravity,
-                                    );
+                                ActiveGameMode::Marathon { start_level } => {
+                                    self.board_widget.new_game_with_goal(GameGoal::Marathon {
+                                        lines: MARATHON_LINES,
+                                        start_level: *start_level,
+                                    });
+                                }
+                                ActiveGameMode::Master => {
+                                    self.board_widget.new_game_with_goal(GameGoal::Master);
+                                }
+                                ActiveGameMode::Sprint => {
+                                    self.board_widget
+                                        .new_game_with_goal(GameGoal::Lines(SPRINT_LINES));
+                                }
+                                ActiveGameMode::Blitz => {
+                                    self.board_widget
+                                        .new_game_with_goal(GameGoal::Time(BLITZ_DURATION));
+                                }
+                                ActiveGameMode::Dig { rows, messiness } => {
+                                    self.board_widget.new_game_with_goal(GameGoal::Dig {
+                                        rows: *rows,
+                                        messiness: *messiness,
+                                    });
                                 }
+                                ActiveGameMode::Survival { accelerating } => {
+                                    self.board_widget.new_game_with_goal(GameGoal::Survival {
+                                        accelerating: *accelerating,
+                                    });
+                                }
+                                ActiveGameMode::LearnMoves { preset, gravity } => {
+                                    self.board_widget
+                                        .new_game_with_preset((**preset).clone(), *gravity);
+                                }
+                                ActiveGameMode::Replay { replay, speed } => {
+                                    self.board_widget.watch_replay((**replay).clone(), *speed);
+                                }
+                                ActiveGameMode::Ai => {
+                                    self.board_widget.watch_ai();
+                                }
+                                ActiveGameMode::Bot => {
+                                    self.board_widget.watch_bot();
+                                }
+                                ActiveGameMode::Versus => self.new_versus_game(),
+                                ActiveGameMode::Online => (),
                             }
                         }
                         GameoverState::EnterMenu => {
                             self.game_state = GameState::Menu;
+                            self.online_widget = None;
+                        }
+                        GameoverState::ExportFumen => {
+                            if self.export_game_fumen().is_ok() {
+                                self.gameover_widget.set_fumen_exported();
+                            }
                         }
                         GameoverState::Pass => (),
                     },
@@ -158,29 +344,52 @@ impl<'a> Game<'a> {
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
+        if keyboard_enhanced {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+
+        self.board_widget.handling = self.menu_widget.settings.handling;
+        self.opponent_widget.handling = self.menu_widget.settings.handling;
+        self.board_widget
+            .set_reports_key_releases(keyboard_enhanced || cfg!(windows));
+        self.opponent_widget
+            .set_reports_key_releases(keyboard_enhanced || cfg!(windows));
+
+        let result = self.game_loop(terminal);
+
+        if keyboard_enhanced {
+            let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+        }
+        ratatui::restore();
+        result
+    }
+
+    fn game_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         loop {
             if self.handle_events()? {
                 break;
             }
 
             match self.game_state {
-                GameState::Game => {
-                    if self.board_widget.update() == BoardState::GameOver {
-                        self.game_state = GameState::GameOver;
-                        match self.active_game_mode {
-                            ActiveGameMode::Endless => {
-                                let score = self.board_widget.board.stats.score;
-                                let lines = self.board_widget.board.stats.cleaned_lines;
-                                let level = self.board_widget.board.stats.level;
-                                self.gameover_widget
-                                    .setup_endless(score, lines, level);
-                            }
-                            ActiveGameMode::LearnMoves { .. } => {
-                                self.gameover_widget.setup_learn_moves();
-                            }
-                        }
-                    }
+                GameState::Game if matches!(self.active_game_mode, ActiveGameMode::Versus) => {
+                    self.update_versus();
+                }
+                GameState::Game if matches!(self.active_game_mode, ActiveGameMode::Online) => {
+                    self.update_online();
+                }
+                GameState::GameOver if matches!(self.active_game_mode, ActiveGameMode::Online) => {
+                    self.poll_online_rounds();
+                    std::thread::sleep(Duration::from_millis(16));
                 }
+                GameState::Game => match self.board_widget.update() {
+                    BoardState::GameOver => self.enter_gameover(false),
+                    BoardState::Finished => self.enter_gameover(true),
+                    _ => (),
+                },
                 _ => std::thread::sleep(Duration::from_millis(16)),
             }
 
@@ -196,10 +405,305 @@ impl<'a> Game<'a> {
             })?;
         }
 
-        ratatui::restore();
         Ok(())
     }
 
+    fn new_versus_game(&mut self) {
+        let seed = rand::random();
+        self.board_widget.new_versus_game(seed);
+        self.opponent_widget.new_versus_game(seed);
+    }
+
+    fn handle_versus_key_event(&mut self, event: KeyEvent) -> bool {
+        if matches!(
+            event.code,
+            KeyCode::Enter | KeyCode::Char('p') | KeyCode::Char('P')
+        ) {
+            if event.is_press() {
+                self.board_widget.pause();
+                self.opponent_widget.pause();
+            }
+            return false;
+        }
+
+        let first = self.board_widget.handle_key_event(event);
+        let second = self.opponent_widget.handle_key_event(event);
+        first == BoardState::Brake || second == BoardState::Brake
+    }
+
+    fn update_versus(&mut self) {
+        let first = self.board_widget.update();
+        let second = self.opponent_widget.run_pending_frames();
+
+        let sent = self.board_widget.board.take_outgoing_garbage();
+        self.opponent_widget.board.queue_garbage(sent);
+        let received = self.opponent_widget.board.take_outgoing_garbage();
+        self.board_widget.board.queue_garbage(received);
+
+        let first_lost = first == BoardState::GameOver;
+        let second_lost = second == BoardState::GameOver;
+
+        let winner = match (first_lost, second_lost) {
+            (false, false) => return,
+            (true, true) => None,
+            (false, true) => Some(1),
+            (true, false) => Some(2),
+        };
+        self.game_state = GameState::GameOver;
+        self.last_replay = None;
+        self.gameover_widget.setup_versus(winner);
+    }
+
+    fn start_online_round(&mut self, seed: u64, round: u32) {
+        self.active_game_mode = ActiveGameMode::Online;
+        self.game_state = GameState::Game;
+        self.last_replay = None;
+        self.board_widget.keymap = Keymap::DEFAULT;
+        self.board_widget.new_versus_game(seed);
+        if let Some(online) = &mut self.online_widget {
+            online.start_round(round);
+        }
+    }
+
+    fn request_rematch(&mut self) {
+        let Some(online) = &mut self.online_widget else {
+            return;
+        };
+        if online.is_closed() {
+            return;
+        }
+
+        let round = online.round();
+        if online.is_host() {
+            let seed = rand::random();
+            online.send(NetMessage::Start {
+                seed,
+                round: round + 1,
+            });
+            self.start_online_round(seed, round + 1);
+        } else {
+            online.send(NetMessage::Rematch { round });
+        }
+    }
+
+    fn poll_online_rounds(&mut self) {
+        while self.game_state == GameState::GameOver {
+            let Some(event) = self
+                .online_widget
+                .as_mut()
+                .and_then(OnlineWidget::poll_round)
+            else {
+                return;
+            };
+
+            match event {
+                RoundEvent::Start(seed, round) => self.start_online_round(seed, round),
+                RoundEvent::Rematch => self.request_rematch(),
+            }
+        }
+    }
+
+    fn handle_online_key_event(&mut self, event: KeyEvent) -> bool {
+        if matches!(
+            event.code,
+            KeyCode::Enter | KeyCode::Char('p') | KeyCode::Char('P')
+        ) {
+            return false;
+        }
+
+        self.board_widget.handle_key_event(event) == BoardState::Brake
+    }
+
+    fn update_online(&mut self) {
+        let state = self.board_widget.update();
+        let Some(online) = &mut self.online_widget else {
+            return;
+        };
+
+        let board = &mut self.board_widget.board;
+        let mut won = false;
+        while let Some(event) = online.poll() {
+            match event {
+                OnlineEvent::Attack(lines) => board.queue_garbage(lines),
+                OnlineEvent::OpponentToppedOut => won = true,
+            }
+        }
+
+        online.send_attack(board.take_outgoing_garbage());
+        online.send_meter(board.pending_garbage_lines());
+        online.send_field(board);
+
+        let lost = state == BoardState::GameOver;
+        let result = if lost {
+            online.send_topped_out();
+            Some(false)
+        } else if won {
+            Some(true)
+        } else if online.is_closed() {
+            None
+        } else {
+            return;
+        };
+
+        self.board_widget.board.timer.pause();
+        self.game_state = GameState::GameOver;
+        self.last_replay = None;
+        self.gameover_widget.setup_online(result);
+    }
+
+    fn saved_mode(&self) -> Option<SavedMode> {
+        match self.active_game_mode {
+            ActiveGameMode::Endless => Some(SavedMode::Endless),
+            ActiveGameMode::Marathon { start_level } => Some(SavedMode::Marathon { start_level }),
+            ActiveGameMode::Master => Some(SavedMode::Master),
+            ActiveGameMode::Sprint => Some(SavedMode::Sprint),
+            ActiveGameMode::Blitz => Some(SavedMode::Blitz),
+            ActiveGameMode::Dig { rows, messiness } => Some(SavedMode::Dig { rows, messiness }),
+            ActiveGameMode::Survival { accelerating } => Some(SavedMode::Survival { accelerating }),
+            _ => None,
+        }
+    }
+
+    fn save_game(&mut self) {
+        let snapshot = self.saved_mode().zip(self.board_widget.snapshot());
+        match snapshot {
+            Some((mode, (board, blocks_manager, replay))) => {
+                let saved_game = SavedGame {
+                    mode,
+                    board,
+                    blocks_manager,
+                    replay,
+                };
+                if saved_game.save().is_err() {
+                    self.save_replay();
+                }
+            }
+            None => self.save_replay(),
+        }
+    }
+
+    fn continue_saved_game(&mut self) {
+        let saved_game = match SavedGame::load() {
+            Ok(saved_game) => {
+                let _ = SavedGame::delete();
+                saved_game
+            }
+            Err(e) => {
+                let notice = match SavedGame::back_up() {
+                    Ok(backup) => format!(
+                        "could not load saved game, kept as {}",
+                        backup.file_name().unwrap_or_default().display()
+                    ),
+                    Err(_) => format!("could not load saved game: {}", e),
+                };
+                self.menu_widget.set_has_saved_game(false);
+                self.menu_widget.set_notice(notice);
+                return;
+            }
+        };
+        self.menu_widget.set_has_saved_game(false);
+
+        self.active_game_mode = match saved_game.mode {
+            SavedMode::Endless => ActiveGameMode::Endless,
+            SavedMode::Marathon { start_level } => ActiveGameMode::Marathon { start_level },
+            SavedMode::Master => ActiveGameMode::Master,
+            SavedMode::Sprint => ActiveGameMode::Sprint,
+            SavedMode::Blitz => ActiveGameMode::Blitz,
+            SavedMode::Dig { rows, messiness } => ActiveGameMode::Dig { rows, messiness },
+            SavedMode::Survival { accelerating } => ActiveGameMode::Survival { accelerating },
+        };
+        self.board_widget.resume(
+            saved_game.board,
+            saved_game.blocks_manager,
+            saved_game.replay,
+        );
+        self.game_state = GameState::Game;
+    }
+
+    fn save_replay(&mut self) {
+        if let Some(replay) = self.board_widget.take_replay() {
+            let _ = replays::save(&replay);
+            self.last_replay = Some(replay);
+        }
+    }
+
+    fn export_game_fumen(&self) -> io::Result<()> {
+        let data = match (&self.active_game_mode, &self.last_replay) {
+            (ActiveGameMode::Replay { replay, .. }, _) => fumen::encode_replay(replay),
+            (_, Some(replay)) => fumen::encode_replay(replay),
+            _ => fumen::encode_grid(self.board_widget.board.grid(), None),
+        };
+        fumens::save(&format!("{}{}", FUMEN_URL, data))
+    }
+
+    fn enter_gameover(&mut self, finished: bool) {
+        self.game_state = GameState::GameOver;
+        self.last_replay = None;
+        self.save_replay();
+
+        let board = &self.board_widget.board;
+        let score = board.stats.score;
+        let lines = board.stats.cleaned_lines;
+        let level = board.stats.level;
+
+        match self.active_game_mode {
+            ActiveGameMode::Endless => {
+                self.gameover_widget.setup_endless(score, lines, level);
+            }
+            ActiveGameMode::Marathon { start_level } => {
+                self.gameover_widget
+                    .setup_marathon(finished, start_level, score, lines, level);
+            }
+            ActiveGameMode::Master => {
+                let time = board.timer.elapsed();
+                self.gameover_widget
+                    .setup_master(finished, time, lines, level);
+            }
+            ActiveGameMode::Sprint => {
+                let time = board.timer.elapsed();
+                self.gameover_widget
+                    .setup_sprint(finished, time, lines, level);
+            }
+            ActiveGameMode::Blitz => {
+                self.gameover_widget
+                    .setup_blitz(finished, score, lines, level);
+            }
+            ActiveGameMode::Dig { rows, messiness } => {
+                let time = board.timer.elapsed();
+                let garbage_cleared = board.stats.garbage_cleared;
+                self.gameover_widget.setup_dig(
+                    finished,
+                    rows,
+                    messiness,
+                    time,
+                    garbage_cleared,
+                    level,
+                );
+            }
+            ActiveGameMode::Survival { accelerating } => {
+                let time = board.timer.elapsed();
+                let garbage_cleared = board.stats.garbage_cleared;
+                self.gameover_widget
+                    .setup_survival(accelerating, time, garbage_cleared, level);
+            }
+            ActiveGameMode::LearnMoves { .. } => {
+                self.gameover_widget.setup_learn_moves(finished);
+            }
+            ActiveGameMode::Replay { .. } => {
+                self.gameover_widget.setup_replay();
+            }
+            ActiveGameMode::Ai | ActiveGameMode::Bot => {
+                self.gameover_widget.setup_ai();
+            }
+            ActiveGameMode::Versus => {
+                self.gameover_widget.setup_versus(None);
+            }
+            ActiveGameMode::Online => {
+                self.gameover_widget.setup_online(Some(false));
+            }
+        }
+    }
+
     fn render_menu(&mut self, frame: &mut Frame) {
         let [_, menu_area, bottom_area] = vertical![*=1, == ROWS, *=1].areas(frame.area());
         let [_, controls_area, _] = vertical![*=1, == 1, == 2].areas(bottom_area);
@@ -227,37 +731,95 @@ impl<'a> Game<'a> {
     }
 
     fn render_game(&mut self, frame: &mut Frame) {
-        let [_, title_area, game_area, _] =
+        let [_, title_area, game_area, bottom_area] =
             vertical![*= 1, == 3, == ROWS, *= 1].areas(frame.area());
-        let [left_area, board_area, next_blocks_area] =
-            horizontal![*= 1, == COLUMNS * 2 + 3, *= 1].areas(game_area);
-        let [hold_area, movement_area, metrics_area] = vertical![*= 1, *= 1, == 8].areas(left_area);
 
         frame.render_widget(
             &self.title,
             title_area.centered_vertically(constraint!(== 1)),
         );
 
-        self.movement_widget.copy_metrics(&self.board_widget.board);
+        let [_, controls_area, _] = vertical![*=1, == 1, == 2].areas(bottom_area);
+
+        if let (ActiveGameMode::Online, Some(online)) =
+            (&self.active_game_mode, &self.online_widget)
+        {
+            let [player_area, opponent_area] = horizontal![*= 1, == COLUMNS + 6].areas(game_area);
+            frame.render_widget(online, opponent_area);
+            self.render_player(frame, player_area, false);
+            return;
+        }
+
+        if !matches!(self.active_game_mode, ActiveGameMode::Versus) {
+            self.render_player(frame, game_area, false);
+            if let (ActiveGameMode::Bot, Some(notice)) =
+                (&self.active_game_mode, self.board_widget.bot_notice())
+            {
+                frame.render_widget(Line::from(notice.red()).centered(), controls_area);
+            }
+            return;
+        }
+
+        let [first_area, second_area] = horizontal![*= 1, *= 1].areas(game_area);
+        self.render_player(frame, first_area, false);
+        self.render_player(frame, second_area, true);
+
+        let controls_hint = line![
+            "P1 ",
+            "[wasd]".cyan(),
+            " move ".dim(),
+            "[q][e]".cyan(),
+            " rotate ".dim(),
+            "[tab]".cyan(),
+            " hold  ".dim(),
+            "P2 ",
+            "[←↓→↑]".cyan(),
+            " move ".dim(),
+            "[1][2]".cyan(),
+            " rotate ".dim(),
+            "[0]".cyan(),
+            " hold  ".dim(),
+            "[p]".cyan(),
+            " pause".dim(),
+        ]
+        .centered();
+        frame.render_widget(controls_hint, controls_area);
+    }
+
+    fn render_player(&mut self, frame: &mut Frame, area: Rect, is_opponent: bool) {
+        let board_widget = if is_opponent {
+            &self.opponent_widget
+        } else {
+            &self.board_widget
+        };
+
+        let meter_width = u16::from(board_widget.shows_garbage_meter());
+        let [left_area, meter_area, board_area, next_blocks_area] =
+            horizontal![*= 1, == meter_width, == COLUMNS * 2 + 3, *= 1].areas(area);
+        let [hold_area, movement_area, metrics_area] = vertical![*= 1, *= 1, == 8].areas(left_area);
+
+        self.movement_widget.copy_metrics(&board_widget.board);
         self.movement_widget.render(movement_area, frame);
 
-        self.metrics_widget.copy_metrics(&self.board_widget.board);
+        self.metrics_widget.copy_metrics(&board_widget.board);
         frame.render_widget(&self.metrics_widget, metrics_area);
 
         #[cfg(debug_assertions)]
         {
-            self.debug_widget.copy_metrics(&self.board_widget.board);
+            self.debug_widget.copy_metrics(&board_widget.board);
             frame.render_widget(&mut self.debug_widget, metrics_area);
         }
 
-        frame.render_widget(&self.board_widget, board_area);
+        frame.render_widget(board_widget, board_area);
+        if board_widget.shows_garbage_meter() {
+            frame.render_widget(GarbageMeterView(&board_widget.board), meter_area);
+        }
 
-        self.held_block_widget
-            .copy_metrics(&self.board_widget.board);
+        self.held_block_widget.copy_metrics(&board_widget.board);
         frame.render_widget(&self.held_block_widget, hold_area);
 
         self.next_blocks_widget
-            .copy_metrics(&self.board_widget.blocks_manager);
+            .copy_metrics(&board_widget.blocks_manager);
         frame.render_widget(&self.next_blocks_widget, next_blocks_area);
     }
 
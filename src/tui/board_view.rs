@@ -0,0 +1,169 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+use tetrus::{
+    board::{Board, Grid},
+    constants::{COLUMNS, GARBAGE_DELAY_DURATION, ROWS},
+};
+
+use crate::colors::ToColor;
+
+pub struct BoardView<'a>(pub &'a Board);
+
+impl Widget for BoardView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let board = self.0;
+        let board_width = COLUMNS * 2;
+        let board_height = ROWS;
+
+        let start_x = area.x + area.width.saturating_sub(board_width) / 2;
+        let start_y = area.y + area.height.saturating_sub(board_height) / 2;
+
+        let mut set_cell = |x: usize, y: usize, ch: char, style: Style| {
+            let cell_x = start_x + (x as u16 * 2) + 1;
+            let cell_y = start_y + y as u16;
+
+            if cell_x < area.right() && cell_y < area.bottom() {
+                let cell = &mut buf[(cell_x, cell_y)];
+                cell.reset();
+                cell.set_char(ch).set_style(style);
+            }
+        };
+
+        for y in 0..ROWS {
+            for x in 0..COLUMNS {
+                if y == 0 || y == 1 {
+                    set_cell(x as usize, y as usize, ' ', Style::default());
+                } else {
+                    set_cell(x as usize, y as usize, '.', Style::default().dim());
+                }
+            }
+        }
+
+        for y in 0..ROWS {
+            for x in 0..COLUMNS {
+                if let Some(cell) = board.grid()[y as usize][x as usize] {
+                    set_cell(
+                        x as usize,
+                        y as usize,
+                        '■',
+                        Style::default().fg(cell.color()),
+                    );
+                }
+            }
+        }
+
+        if let Some(piece) = board.active_piece {
+            let (square_x, square_y) = piece.coord;
+            let active_coords = piece.block.get_coordinates(piece.rotation);
+
+            if let Some((ghost_x, ghost_y)) = board.get_ghost_coord() {
+                if (ghost_x, ghost_y) != (square_x, square_y) {
+                    for (block_x, block_y) in active_coords {
+                        let board_x = ghost_x + block_x as isize;
+                        let board_y = ghost_y + block_y as isize;
+                        let overlaps_active = active_coords.iter().any(|(ax, ay)| {
+                            square_x + *ax as isize == board_x && square_y + *ay as isize == board_y
+                        });
+
+                        if !overlaps_active
+                            && board_x >= 0
+                            && board_x < COLUMNS as isize
+                            && board_y >= 0
+                            && board_y < ROWS as isize
+                        {
+                            set_cell(
+                                board_x as usize,
+                                board_y as usize,
+                                '□',
+                                Style::default().fg(Color::White).dim(),
+                            );
+                        }
+                    }
+                }
+            }
+
+            let color = piece.block.color();
+            for (block_x, block_y) in active_coords {
+                let board_x = square_x + block_x as isize;
+                let board_y = square_y + block_y as isize;
+                if board_x >= 0
+                    && board_x < COLUMNS as isize
+                    && board_y >= 0
+                    && board_y < ROWS as isize
+                {
+                    set_cell(
+                        board_x as usize,
+                        board_y as usize,
+                        '□',
+                        Style::default().fg(color),
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub struct GarbageMeterView<'a>(pub &'a Board);
+
+impl Widget for GarbageMeterView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let board = self.0;
+        let start_y = area.y + area.height.saturating_sub(ROWS) / 2;
+        let bottom = (start_y + ROWS).min(area.bottom());
+        let top = start_y + 2;
+
+        let pending = board.pending_garbage.iter().flat_map(|garbage| {
+            let color = if board.is_garbage_ready(garbage) {
+                Color::Red
+            } else {
+                Color::Yellow
+            };
+            std::iter::repeat_n(color, garbage.lines)
+        });
+        let rise = board.until_garbage_rise().map(|until| {
+            if until <= GARBAGE_DELAY_DURATION {
+                Color::Red
+            } else {
+                Color::Yellow
+            }
+        });
+
+        for (color, y) in pending.chain(rise).zip((top..bottom).rev()) {
+            let cell = &mut buf[(area.x, y)];
+            cell.reset();
+            cell.set_char('┃').set_style(Style::default().fg(color));
+        }
+    }
+}
+
+pub struct MiniBoardView<'a>(pub &'a Grid);
+
+impl Widget for MiniBoardView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let visible_rows = self.0.len() - 2;
+        let start_x = area.x + area.width.saturating_sub(COLUMNS) / 2;
+        let start_y = area.y + area.height.saturating_sub(visible_rows as u16) / 2;
+
+        for (y, row) in self.0[2..].iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let cell_x = start_x + x as u16;
+                let cell_y = start_y + y as u16;
+                if cell_x >= area.right() || cell_y >= area.bottom() {
+                    continue;
+                }
+
+                let (ch, style) = match cell {
+                    Some(cell) => ('■', Style::default().fg(cell.color())),
+                    None => ('.', Style::default().dim()),
+                };
+                let cell = &mut buf[(cell_x, cell_y)];
+                cell.reset();
+                cell.set_char(ch).set_style(style);
+            }
+        }
+    }
+}
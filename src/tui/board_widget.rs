@@ -1,14 +1,33 @@
-use std::time::{Duration, Instant};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     buffer::Buffer,
     layout::{Margin, Rect},
-    macros::{constraint, text},
+    macros::{constraint, line, text},
+    style::Stylize,
     widgets::{Block, Clear, Widget},
 };
 
-use crate::{blocks_manager::BlocksManager, board::Board};
+use tetrus::{
+    action::GameAction,
+    ai::Ai,
+    blocks_manager::BlocksManager,
+    board::{Board, GameGoal, TickOutcome},
+    constants::{AI_PLACEMENT_DELAY_FRAMES, FRAME_DURATION},
+    garbage::{GarbageGenerator, MAX_MESSINESS},
+    preset::Preset,
+    replay::{Replay, ReplayPlayer},
+    tbp::TbpSession,
+};
+
+use crate::{
+    settings::Handling,
+    tui::{board_view::BoardView, keymap::Keymap},
+};
 
 #[derive(Default, PartialEq, Eq)]
 pub enum BoardState {
@@ -16,22 +35,60 @@ pub enum BoardState {
     Pass,
     Brake,
     GameOver,
+    Finished,
     Paused,
+    Save,
+    ExportFumen,
+}
+
+#[derive(Clone, Copy)]
+enum Autoplay {
+    Ai(Ai),
+    Bot,
+}
+
+struct Drill {
+    preset: Preset,
+    gravity: usize,
+    locks: usize,
+}
+
+#[derive(Default)]
+struct HeldKeys {
+    left: bool,
+    right: bool,
+    shift: Option<GameAction>,
+    shift_held_for: Duration,
+    shift_repeat_acc: Duration,
+    soft_drop: bool,
+    soft_drop_acc: Duration,
 }
 
 pub struct BoardWidget {
     pub board: Board,
     pub blocks_manager: BlocksManager,
+    pub handling: Handling,
+    pub keymap: Keymap,
 
     tick_interval: Duration,
     last_tick: Instant,
-    acc_time: Duration,
+    frame_acc: Duration,
+    reports_key_releases: bool,
+    held_keys: HeldKeys,
+    recording: Option<Replay>,
+    playback: Option<ReplayPlayer>,
+    playback_speed: u32,
+    autoplay: Option<Autoplay>,
+    autoplay_wait: u32,
+    bot: Option<TbpSession>,
+    bot_notice: Option<String>,
+    fumen_exported: bool,
+    drill: Option<Drill>,
+    incoming_garbage: Option<GarbageGenerator>,
 }
 
 impl BoardWidget {
     pub fn new() -> Self {
-        let tick_60fps_interval: Duration = Duration::from_secs_f32(1.0 / 60.0);
-
         #[cfg(feature = "vhs")]
         let blocks_manager = BlocksManager::with_seed(123456789);
 
@@ -39,143 +96,555 @@ impl BoardWidget {
         let blocks_manager = BlocksManager::new();
 
         Self {
-            tick_interval: tick_60fps_interval,
+            tick_interval: FRAME_DURATION,
             board: Board::new(),
             blocks_manager,
+            handling: Handling::default(),
+            keymap: Keymap::DEFAULT,
             last_tick: Instant::now(),
-            acc_time: Duration::ZERO,
+            frame_acc: Duration::ZERO,
+            reports_key_releases: false,
+            held_keys: HeldKeys::default(),
+            recording: None,
+            playback: None,
+            playback_speed: 1,
+            autoplay: None,
+            autoplay_wait: 0,
+            bot: None,
+            bot_notice: None,
+            fumen_exported: false,
+            drill: None,
+            incoming_garbage: None,
         }
     }
 
+    pub fn set_reports_key_releases(&mut self, reports_key_releases: bool) {
+        self.reports_key_releases = reports_key_releases;
+    }
+
     pub fn new_game(&mut self) {
-        self.board.new_game();
+        self.new_game_with_goal(GameGoal::None);
+    }
+
+    pub fn new_game_with_goal(&mut self, goal: GameGoal) {
+        self.board.new_game_with_goal(goal);
         self.last_tick = Instant::now();
-        self.acc_time = Duration::ZERO;
+        self.frame_acc = Duration::ZERO;
+        self.held_keys = HeldKeys::default();
         self.blocks_manager.reset();
+        self.board
+            .setup_goal_garbage(self.blocks_manager.current_seed());
+        self.recording = Some(Replay::new(self.blocks_manager.current_seed(), goal));
+        self.playback = None;
+        self.playback_speed = 1;
+        self.autoplay = None;
+        self.drill = None;
+        self.incoming_garbage = None;
+    }
+
+    pub fn new_versus_game(&mut self, seed: u64) {
+        self.board.new_game();
+        self.last_tick = Instant::now();
+        self.frame_acc = Duration::ZERO;
+        self.held_keys = HeldKeys::default();
+        self.blocks_manager.reset_with_seed(seed);
+        self.recording = None;
+        self.playback = None;
+        self.playback_speed = 1;
+        self.autoplay = None;
+        self.drill = None;
+        self.incoming_garbage = Some(GarbageGenerator::new(seed, MAX_MESSINESS));
+    }
+
+    fn receive_garbage(&mut self, rows: usize) -> bool {
+        let Some(garbage) = &mut self.incoming_garbage else {
+            return true;
+        };
+        if rows == 0 {
+            return true;
+        }
+
+        let hole = garbage.next_hole();
+        if self.board.add_garbage(rows, hole) {
+            true
+        } else {
+            self.board.timer.pause();
+            false
+        }
+    }
+
+    fn place_released_garbage(&mut self) -> bool {
+        self.board
+            .take_released_garbage()
+            .into_iter()
+            .all(|rows| self.receive_garbage(rows))
+    }
+
+    pub fn watch_ai(&mut self) {
+        self.new_game();
+        self.recording = None;
+        self.autoplay = Some(Autoplay::Ai(Ai::default()));
+        self.autoplay_wait = 0;
+    }
+
+    pub fn set_bot(&mut self, bot: TbpSession) {
+        self.bot = Some(bot);
+    }
+
+    pub fn watch_bot(&mut self) {
+        self.new_game();
+        self.recording = None;
+        self.autoplay = Some(Autoplay::Ai(Ai::default()));
+        self.autoplay_wait = 0;
+        if let Some(bot) = &mut self.bot {
+            match bot.restart() {
+                Ok(()) => self.autoplay = Some(Autoplay::Bot),
+                Err(e) => self.drop_bot(e),
+            }
+        }
+    }
+
+    pub fn bot_notice(&self) -> Option<&str> {
+        self.bot_notice.as_deref()
+    }
+
+    fn drop_bot(&mut self, error: io::Error) {
+        self.bot = None;
+        self.autoplay = Some(Autoplay::Ai(Ai::default()));
+        self.bot_notice = Some(format!("bot stopped: {}, built-in ai took over", error));
+    }
+
+    pub fn watch_replay(&mut self, replay: Replay, speed: u32) {
+        self.board.new_game_with_goal(replay.goal);
+        self.last_tick = Instant::now();
+        self.frame_acc = Duration::ZERO;
+        self.held_keys = HeldKeys::default();
+        self.blocks_manager.reset_with_seed(replay.seed);
+        self.board.setup_goal_garbage(replay.seed);
+        self.recording = None;
+        self.incoming_garbage = None;
+        self.playback = Some(ReplayPlayer::new(replay));
+        self.playback_speed = speed.max(1);
+        self.autoplay = None;
+        self.drill = None;
+    }
+
+    pub fn snapshot(&self) -> Option<(Board, BlocksManager, Replay)> {
+        let replay = self.recording.clone()?;
+        Some((self.board.clone(), self.blocks_manager.clone(), replay))
+    }
+
+    pub fn resume(&mut self, board: Board, blocks_manager: BlocksManager, replay: Replay) {
+        self.board = board;
+        self.blocks_manager = blocks_manager;
+        self.last_tick = Instant::now();
+        self.frame_acc = Duration::ZERO;
+        self.held_keys = HeldKeys::default();
+        self.recording = Some(replay);
+        self.playback = None;
+        self.playback_speed = 1;
+        self.autoplay = None;
+        self.drill = None;
+        self.incoming_garbage = None;
+
+        if !self.board.is_paused() {
+            self.board.pause();
+        }
+    }
+
+    pub fn shows_garbage_meter(&self) -> bool {
+        self.incoming_garbage.is_some() || self.board.until_garbage_rise().is_some()
+    }
+
+    pub fn pause(&mut self) {
+        self.board.pause();
+        self.held_keys = HeldKeys::default();
+    }
+
+    pub fn set_fumen_exported(&mut self) {
+        self.fumen_exported = true;
+    }
+
+    pub fn take_replay(&mut self) -> Option<Replay> {
+        let mut replay = self.recording.take()?;
+        replay.finish(self.board.timer.frames());
+        Some(replay)
     }
 
-    pub fn new_game_with_preset(
-        &mut self,
-        grid: crate::board::Grid,
-        starting_pieces: &[crate::blocks::Block],
-        gravity: usize,
-    ) {
-        self.board.new_with_grid_and_gravity(grid, gravity);
+    pub fn new_game_with_preset(&mut self, preset: Preset, gravity: usize) {
+        self.board.new_with_grid_and_gravity(preset.grid, gravity);
+        self.board.hold_state.block = preset.hold;
         self.last_tick = Instant::now();
-        self.acc_time = Duration::ZERO;
+        self.frame_acc = Duration::ZERO;
+        self.held_keys = HeldKeys::default();
         self.blocks_manager.reset();
-        self.blocks_manager.set_next_blocks_slice(starting_pieces);
+        self.blocks_manager.set_next_blocks_slice(&preset.pieces);
+        self.drill = Some(Drill {
+            preset,
+            gravity,
+            locks: 0,
+        });
+        self.incoming_garbage = None;
+        self.recording = None;
+        self.playback = None;
+        self.playback_speed = 1;
+        self.autoplay = None;
     }
 
     pub fn handle_key_event(&mut self, event: KeyEvent) -> BoardState {
-        if self.board.is_paused() {
-            return match event.code {
-                KeyCode::Enter | KeyCode::Char('p') | KeyCode::Char('P') => {
-                    self.board.pause();
-                    BoardState::Paused
-                }
-                KeyCode::Esc => BoardState::Brake,
-                _ => BoardState::Paused,
+        if event.code == KeyCode::Esc {
+            return if event.is_release() {
+                BoardState::Pass
+            } else {
+                BoardState::Brake
             };
         }
 
-        match event.code {
-            KeyCode::Left | KeyCode::Right => {
-                self.board.move_block_x_axis(event.code);
-                BoardState::Pass
+        if self.can_save()
+            && matches!(event.code, KeyCode::Char('s') | KeyCode::Char('S'))
+            && !event.is_release()
+        {
+            return BoardState::Save;
+        }
+
+        if self.can_export_fumen()
+            && matches!(event.code, KeyCode::Char('f') | KeyCode::Char('F'))
+            && !event.is_release()
+        {
+            return BoardState::ExportFumen;
+        }
+
+        let Some(action) = self.keymap.action(event.code) else {
+            return BoardState::Pass;
+        };
+
+        if event.is_release() {
+            self.release_action(action);
+            return BoardState::Pass;
+        }
+
+        if self.playback.is_some() || self.autoplay.is_some() {
+            if action == GameAction::Pause && !event.is_repeat() {
+                self.board.pause();
             }
-            KeyCode::Down => {
-                if self.board.stats.level == 0 {
-                    let _ = self.board.move_block_down();
-                } else {
-                    let _ = self.board.move_block_down_or_set();
-                }
+            return if self.board.is_paused() {
+                BoardState::Paused
+            } else {
                 BoardState::Pass
+            };
+        }
+
+        if self.reports_key_releases && (event.is_repeat() || self.is_action_held(action)) {
+            return BoardState::Pass;
+        }
+
+        if self.board.is_paused() && action != GameAction::Pause {
+            return BoardState::Paused;
+        }
+
+        let _ = self.apply(action);
+
+        match action {
+            GameAction::MoveLeft | GameAction::MoveRight if self.reports_key_releases => {
+                self.press_shift_action(action);
             }
-            KeyCode::Up
-            | KeyCode::Char('z')
-            | KeyCode::Char('Z')
-            | KeyCode::Char('x')
-            | KeyCode::Char('X')
-            | KeyCode::Char('a')
-            | KeyCode::Char('A') => {
-                let _ = self.board.rotate_block(event.code);
-                BoardState::Pass
+            GameAction::SoftDrop if self.reports_key_releases => {
+                self.held_keys.soft_drop = true;
+                self.held_keys.soft_drop_acc = Duration::ZERO;
             }
-            KeyCode::Char(' ') => {
-                while self.board.move_block_down() {}
-                self.board.lock_current_block();
-                self.acc_time = Duration::ZERO;
-                BoardState::Pass
+            GameAction::Pause => self.held_keys = HeldKeys::default(),
+            _ => (),
+        }
+
+        if self.board.is_paused() {
+            BoardState::Paused
+        } else {
+            BoardState::Pass
+        }
+    }
+
+    fn can_export_fumen(&self) -> bool {
+        self.board.is_paused() && self.incoming_garbage.is_none()
+    }
+
+    fn can_save(&self) -> bool {
+        self.board.is_paused() && self.recording.is_some()
+    }
+
+    fn is_action_held(&self, action: GameAction) -> bool {
+        match action {
+            GameAction::MoveLeft => self.held_keys.left,
+            GameAction::MoveRight => self.held_keys.right,
+            GameAction::SoftDrop => self.held_keys.soft_drop,
+            _ => false,
+        }
+    }
+
+    fn press_shift_action(&mut self, action: GameAction) {
+        match action {
+            GameAction::MoveLeft => self.held_keys.left = true,
+            GameAction::MoveRight => self.held_keys.right = true,
+            _ => return,
+        }
+
+        self.held_keys.shift = Some(action);
+        self.held_keys.shift_held_for = Duration::ZERO;
+        self.held_keys.shift_repeat_acc = Duration::ZERO;
+    }
+
+    fn release_action(&mut self, action: GameAction) {
+        let other = match action {
+            GameAction::MoveLeft => {
+                self.held_keys.left = false;
+                self.held_keys.right.then_some(GameAction::MoveRight)
             }
-            KeyCode::Char('c') | KeyCode::Char('C') => {
-                let _ = self.board.hold_block(&mut self.blocks_manager);
-                BoardState::Pass
+            GameAction::MoveRight => {
+                self.held_keys.right = false;
+                self.held_keys.left.then_some(GameAction::MoveLeft)
             }
-            KeyCode::Enter | KeyCode::Char('p') | KeyCode::Char('P') => {
-                self.board.pause();
-                BoardState::Paused
+            GameAction::SoftDrop => {
+                self.held_keys.soft_drop = false;
+                return;
             }
-            KeyCode::Esc => BoardState::Brake,
-            _ => BoardState::Pass,
+            _ => return,
+        };
+
+        if self.held_keys.shift == Some(action) {
+            self.held_keys.shift = other;
+            self.held_keys.shift_held_for = Duration::ZERO;
+            self.held_keys.shift_repeat_acc = Duration::ZERO;
         }
     }
 
-    pub fn update(&mut self) -> BoardState {
-        let current_time = Instant::now();
-        let delta_time = current_time.duration_since(self.last_tick);
-        self.last_tick = current_time;
+    fn apply(&mut self, action: GameAction) -> bool {
+        if let Some(replay) = &mut self.recording {
+            if action != GameAction::Pause {
+                replay.record(self.board.timer.frames(), action);
+            }
+        }
 
-        if self.board.is_paused() {
-            let elapsed = current_time.elapsed();
-            if elapsed < self.tick_interval {
-                std::thread::sleep(self.tick_interval - elapsed);
-            };
+        self.board.apply_action(action, &mut self.blocks_manager)
+    }
 
-            return BoardState::Paused;
+    fn apply_auto_shift(&mut self, delta_time: Duration) {
+        let Some(action) = self.held_keys.shift else {
+            return;
+        };
+
+        let das = self.handling.das();
+        let arr = self.handling.arr();
+        let was_charged = self.held_keys.shift_held_for >= das;
+
+        self.held_keys.shift_held_for += delta_time;
+        if self.held_keys.shift_held_for < das {
+            return;
+        }
+
+        if arr.is_zero() {
+            while self.apply(action) {}
+            return;
+        }
+
+        if was_charged {
+            self.held_keys.shift_repeat_acc += delta_time;
+        } else {
+            let _ = self.apply(action);
+            self.held_keys.shift_repeat_acc = self.held_keys.shift_held_for - das;
         }
 
-        self.acc_time += delta_time;
+        while self.held_keys.shift_repeat_acc >= arr {
+            self.held_keys.shift_repeat_acc -= arr;
+            let _ = self.apply(action);
+        }
+    }
+
+    fn apply_soft_drop(&mut self, delta_time: Duration) {
+        if !self.held_keys.soft_drop {
+            return;
+        }
+
+        let gravity = if self.board.stats.fall_speed.is_zero() {
+            Duration::from_secs(1)
+        } else {
+            self.board.stats.fall_speed
+        };
+        let interval = gravity / self.handling.soft_drop_factor.max(1);
 
+        self.held_keys.soft_drop_acc += delta_time;
+        while self.held_keys.soft_drop_acc >= interval {
+            self.held_keys.soft_drop_acc -= interval;
+            if !self.apply(GameAction::SoftDrop) {
+                self.held_keys.soft_drop_acc = Duration::ZERO;
+                break;
+            }
+        }
+    }
+
+    fn play_ai_turn(&mut self, ai: &Ai) {
         if !self.board.is_block_falling() {
-            let block = self.blocks_manager.get_next_block();
-            if !self.board.spawn_next_block(&block) {
-                self.board.timer.pause();
-                return BoardState::GameOver;
-            };
+            return;
+        }
+
+        self.autoplay_wait += 1;
+        if self.autoplay_wait < AI_PLACEMENT_DELAY_FRAMES {
+            return;
+        }
+        self.autoplay_wait = 0;
+
+        if let Some(placement) = ai.best_placement(&self.board, &self.blocks_manager) {
+            for action in placement.actions {
+                let _ = self.apply(action);
+            }
+        }
+    }
+
+    fn play_bot_turn(&mut self) {
+        let Some(bot) = &mut self.bot else {
+            return;
+        };
+
+        match bot.poll(&self.board, &self.blocks_manager) {
+            Ok(Some(actions)) => {
+                for action in actions {
+                    let _ = self.apply(action);
+                }
+            }
+            Ok(None) => (),
+            Err(e) => self.drop_bot(e),
+        }
+    }
+
+    fn has_objective(&self) -> bool {
+        self.drill
+            .as_ref()
+            .is_some_and(|drill| drill.preset.objective.is_some())
+    }
+
+    fn check_drill(&mut self) -> Option<BoardState> {
+        let drill = self.drill.as_mut()?;
+        let objective = drill.preset.objective?;
+        let clear = self.board.last_clear.take()?;
+        drill.locks += 1;
+
+        if objective.is_met(&clear) {
+            return Some(BoardState::Finished);
+        }
+
+        let is_out_of_pieces =
+            !drill.preset.pieces.is_empty() && drill.locks >= drill.preset.pieces.len();
+        if clear.lines > 0 || is_out_of_pieces {
+            self.restart_drill();
         }
+        None
+    }
+
+    fn restart_drill(&mut self) {
+        if let Some(drill) = self.drill.take() {
+            self.new_game_with_preset(drill.preset, drill.gravity);
+        }
+    }
 
-        if self.board.stats.fall_speed > Duration::ZERO {
-            while self.acc_time >= self.board.stats.fall_speed {
-                self.acc_time -= self.board.stats.fall_speed;
-                let _ = self.board.move_block_down_or_set();
+    pub fn step(&mut self) -> BoardState {
+        if let Some(player) = &mut self.playback {
+            let frame = self.board.timer.frames();
+            if !self.board.is_paused() {
+                if player.is_finished(frame) {
+                    return BoardState::Finished;
+                }
+                while let Some(action) = player.next_action(frame) {
+                    let _ = self.board.apply_action(action, &mut self.blocks_manager);
+                }
             }
         }
 
-        if self.board.stats.level > 0 {
-            self.board.check_lock_delay();
+        if let Some(state) = self.check_drill() {
+            return state;
+        }
+
+        let outcome = self.board.tick(&mut self.blocks_manager);
+        if !self.place_released_garbage() {
+            return BoardState::GameOver;
+        }
+
+        match outcome {
+            TickOutcome::Playing => {
+                self.apply_auto_shift(FRAME_DURATION);
+                self.apply_soft_drop(FRAME_DURATION);
+                match self.autoplay {
+                    Some(Autoplay::Ai(ai)) => self.play_ai_turn(&ai),
+                    Some(Autoplay::Bot) => self.play_bot_turn(),
+                    None => (),
+                }
+                BoardState::Pass
+            }
+            TickOutcome::Paused => BoardState::Paused,
+            TickOutcome::ToppedOut if self.has_objective() => {
+                self.restart_drill();
+                BoardState::Pass
+            }
+            TickOutcome::ToppedOut => BoardState::GameOver,
+            TickOutcome::GoalReached => BoardState::Finished,
         }
+    }
 
-        let elapsed = current_time.elapsed();
+    pub fn update(&mut self) -> BoardState {
+        let started_at = Instant::now();
+        let state = self.run_pending_frames();
+
+        let elapsed = started_at.elapsed();
         if elapsed < self.tick_interval {
             std::thread::sleep(self.tick_interval - elapsed);
         };
 
-        BoardState::Pass
+        state
+    }
+
+    pub fn run_pending_frames(&mut self) -> BoardState {
+        let current_time = Instant::now();
+        self.frame_acc += current_time.duration_since(self.last_tick) * self.playback_speed;
+        self.last_tick = current_time;
+
+        let mut state = if self.board.is_paused() {
+            self.frame_acc = Duration::ZERO;
+            BoardState::Paused
+        } else {
+            self.fumen_exported = false;
+            BoardState::Pass
+        };
+
+        while self.frame_acc >= FRAME_DURATION {
+            self.frame_acc -= FRAME_DURATION;
+            state = self.step();
+            if matches!(state, BoardState::GameOver | BoardState::Finished) {
+                return state;
+            }
+        }
+
+        state
     }
 }
 
 impl Widget for &BoardWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        self.board.render(area, buf);
+        BoardView(&self.board).render(area, buf);
 
         if self.board.is_paused() {
-            let block_area = area.centered(constraint!(== 50%), constraint!(== 5));
+            let mut pause_text = text!["pause", ""];
+            if self.can_save() {
+                pause_text.push_line(line!["[s]".cyan(), " save & quit".dim()]);
+            }
+            if self.fumen_exported {
+                pause_text.push_line(line!["fumen saved".green()]);
+            } else if self.can_export_fumen() {
+                pause_text.push_line(line!["[f]".cyan(), " export fumen".dim()]);
+            }
+            let pause_text = pause_text.centered();
+
+            let text_height = pause_text.height() as u16;
+            let block_area = area.centered(constraint!(== 90%), constraint!(== text_height + 4));
             let text_area = block_area
                 .inner(Margin::new(1, 1))
-                .centered_vertically(constraint!(== 1));
-
-            let pause_text = text!["pause"].centered();
+                .centered_vertically(constraint!(== text_height));
 
             Clear.render(block_area, buf);
             Block::bordered().render(block_area, buf);
@@ -183,3 +652,70 @@ impl Widget for &BoardWidget {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEventKind, KeyModifiers};
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn release(code: KeyCode) -> KeyEvent {
+        KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Release)
+    }
+
+    fn script(pieces: u64) -> Vec<(u64, KeyEvent)> {
+        let mut events = Vec::new();
+        for piece in 0..pieces {
+            let start = piece * 60;
+            let shift = if piece % 2 == 0 {
+                KeyCode::Left
+            } else {
+                KeyCode::Right
+            };
+            events.push((start, press(shift)));
+            for turn in 0..piece % 4 {
+                events.push((start + 2 + turn, press(KeyCode::Up)));
+            }
+            events.push((start + 5 + piece * 7 % 40, release(shift)));
+            if piece % 3 == 0 {
+                events.push((start + 48, press(KeyCode::Char('c'))));
+            }
+            events.push((start + 50, press(KeyCode::Char(' '))));
+        }
+        events
+    }
+
+    #[test]
+    fn replays_recorded_game_with_auto_shift() {
+        let events = script(30);
+        let mut widget = BoardWidget::new();
+        widget.set_reports_key_releases(true);
+        widget.new_game();
+        for frame in 0..2000 {
+            for (_, event) in events.iter().filter(|(at, _)| *at == frame) {
+                widget.handle_key_event(*event);
+            }
+            if matches!(widget.step(), BoardState::GameOver | BoardState::Finished) {
+                break;
+            }
+        }
+        let replay = widget.take_replay().unwrap();
+        let shifts = replay
+            .inputs
+            .iter()
+            .filter(|input| matches!(input.action, GameAction::MoveLeft | GameAction::MoveRight))
+            .count();
+        assert!(shifts > 30);
+
+        let mut playback = BoardWidget::new();
+        playback.watch_replay(replay, 1);
+        while !matches!(playback.step(), BoardState::GameOver | BoardState::Finished) {}
+
+        assert_eq!(playback.board.grid(), widget.board.grid());
+        assert_eq!(playback.board.stats, widget.board.stats);
+        assert_eq!(playback.board.timer.frames(), widget.board.timer.frames());
+    }
+}
@@ -6,7 +6,7 @@ use ratatui::{
 };
 use std::time::{Duration, Instant};
 
-use crate::{blocks::Rotation, board::Board};
+use tetrus::{blocks::Rotation, board::Board};
 
 #[cfg(debug_assertions)]
 pub struct DebugWidget {
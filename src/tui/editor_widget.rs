@@ -0,0 +1,302 @@
+use std::io;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    macros::{horizontal, span},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::Widget,
+};
+use strum::EnumCount;
+
+use tetrus::{
+    blocks::Block,
+    board::{Cell, Grid},
+    constants::{COLUMNS, ROWS},
+    preset::Preset,
+};
+
+use crate::{colors::ToColor, presets};
+
+const BRUSHES: [Cell; 8] = [
+    Cell::Garbage,
+    Cell::Block(Block::Line),
+    Cell::Block(Block::J),
+    Cell::Block(Block::L),
+    Cell::Block(Block::Square),
+    Cell::Block(Block::S),
+    Cell::Block(Block::T),
+    Cell::Block(Block::Z),
+];
+const MAX_QUEUE_LEN: usize = Block::COUNT * 2;
+const HIDDEN_ROWS: usize = 2;
+
+#[derive(Default, PartialEq, Eq)]
+pub enum EditorState {
+    #[default]
+    Pass,
+    Test(Box<Preset>),
+    Back,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+enum EditorFocus {
+    #[default]
+    Grid,
+    Queue,
+    Hold,
+}
+
+pub struct EditorWidget {
+    grid: Grid,
+    cursor: (usize, usize),
+    brush_index: usize,
+    queue: Vec<Block>,
+    hold: Option<Block>,
+    focus: EditorFocus,
+    status: Option<io::Result<String>>,
+}
+
+impl EditorWidget {
+    pub fn new() -> Self {
+        Self {
+            grid: [[None; COLUMNS as usize]; ROWS as usize],
+            cursor: (0, ROWS as usize - 1),
+            brush_index: 0,
+            queue: Vec::new(),
+            hold: None,
+            focus: EditorFocus::default(),
+            status: None,
+        }
+    }
+
+    fn preset(&self) -> Preset {
+        Preset {
+            title: String::new(),
+            description: String::new(),
+            grid: self.grid,
+            pieces: self.queue.clone(),
+            hold: self.hold,
+            gravity: None,
+            objective: None,
+        }
+    }
+
+    fn brush_from_key(c: char) -> Option<Cell> {
+        match c.to_ascii_uppercase() {
+            'X' | 'G' => Some(Cell::Garbage),
+            letter => Block::from_letter(letter).map(Cell::Block),
+        }
+    }
+
+    fn block_from_key(c: char) -> Option<Block> {
+        Block::from_letter(c.to_ascii_uppercase())
+    }
+
+    fn paint(&mut self) {
+        let (x, y) = self.cursor;
+        let brush = BRUSHES[self.brush_index];
+        let cell = &mut self.grid[y][x];
+        *cell = if *cell == Some(brush) {
+            None
+        } else {
+            Some(brush)
+        };
+    }
+
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.cursor;
+        let x = x.saturating_add_signed(dx).min(COLUMNS as usize - 1);
+        let y = y
+            .saturating_add_signed(dy)
+            .clamp(HIDDEN_ROWS, ROWS as usize - 1);
+        self.cursor = (x, y);
+    }
+
+    pub fn handle_key_event(&mut self, event: KeyEvent) -> EditorState {
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            if event.code == KeyCode::Char('s') {
+                self.status = Some(presets::save(&self.preset()));
+            }
+            return EditorState::Pass;
+        }
+
+        self.status = None;
+        match event.code {
+            KeyCode::Esc => return EditorState::Back,
+            KeyCode::Enter => return EditorState::Test(Box::new(self.preset())),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    EditorFocus::Grid => EditorFocus::Queue,
+                    EditorFocus::Queue => EditorFocus::Hold,
+                    EditorFocus::Hold => EditorFocus::Grid,
+                };
+                return EditorState::Pass;
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    EditorFocus::Grid => EditorFocus::Hold,
+                    EditorFocus::Queue => EditorFocus::Grid,
+                    EditorFocus::Hold => EditorFocus::Queue,
+                };
+                return EditorState::Pass;
+            }
+            _ => (),
+        }
+
+        match self.focus {
+            EditorFocus::Grid => match event.code {
+                KeyCode::Left => self.move_cursor(-1, 0),
+                KeyCode::Right => self.move_cursor(1, 0),
+                KeyCode::Up => self.move_cursor(0, -1),
+                KeyCode::Down => self.move_cursor(0, 1),
+                KeyCode::Char(' ') => self.paint(),
+                KeyCode::Backspace | KeyCode::Delete => {
+                    let (x, y) = self.cursor;
+                    self.grid[y][x] = None;
+                }
+                KeyCode::Char(c) => {
+                    if let Some(index) = EditorWidget::brush_from_key(c)
+                        .and_then(|brush| BRUSHES.iter().position(|&b| b == brush))
+                    {
+                        self.brush_index = index;
+                    }
+                }
+                _ => (),
+            },
+            EditorFocus::Queue => match event.code {
+                KeyCode::Backspace | KeyCode::Delete => {
+                    self.queue.pop();
+                }
+                KeyCode::Char(c) if self.queue.len() < MAX_QUEUE_LEN => {
+                    if let Some(block) = EditorWidget::block_from_key(c) {
+                        self.queue.push(block);
+                    }
+                }
+                _ => (),
+            },
+            EditorFocus::Hold => match event.code {
+                KeyCode::Backspace | KeyCode::Delete => self.hold = None,
+                KeyCode::Char(c) => {
+                    if let Some(block) = EditorWidget::block_from_key(c) {
+                        self.hold = Some(block);
+                    }
+                }
+                _ => (),
+            },
+        }
+        EditorState::Pass
+    }
+
+    fn heading(&self, text: &'static str, focus: EditorFocus) -> Line<'static> {
+        if self.focus == focus {
+            Line::from(span!("- {} -", text).green().bold())
+        } else {
+            Line::from(text)
+        }
+    }
+
+    fn block_span(block: Block) -> Span<'static> {
+        span!(block.letter()).fg(block.color()).bold()
+    }
+
+    fn render_grid(&self, area: Rect, buf: &mut Buffer) {
+        let start_x = area.x + area.width.saturating_sub(COLUMNS * 2) / 2;
+        let start_y = area.y + area.height.saturating_sub(ROWS) / 2;
+
+        for y in HIDDEN_ROWS..ROWS as usize {
+            for x in 0..COLUMNS as usize {
+                let cell_x = start_x + (x as u16 * 2) + 1;
+                let cell_y = start_y + y as u16;
+                if cell_x >= area.right() || cell_y >= area.bottom() {
+                    continue;
+                }
+
+                let (ch, mut style) = match self.grid[y][x] {
+                    Some(cell) => ('■', Style::default().fg(cell.color())),
+                    None => ('.', Style::default().dim()),
+                };
+                if self.focus == EditorFocus::Grid && (x, y) == self.cursor {
+                    style = Style::default()
+                        .fg(BRUSHES[self.brush_index].color())
+                        .reversed();
+                }
+
+                let cell = &mut buf[(cell_x, cell_y)];
+                cell.reset();
+                cell.set_char(ch).set_style(style);
+            }
+        }
+    }
+}
+
+impl Widget for &mut EditorWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [left_area, board_area, right_area] =
+            horizontal![*= 1, == COLUMNS * 2 + 3, *= 1].areas(area);
+
+        let mut brush_text = Text::from(vec![
+            Line::raw(""),
+            Line::raw(""),
+            self.heading("brush", EditorFocus::Grid),
+            Line::raw(""),
+        ]);
+        for (i, brush) in BRUSHES.iter().enumerate() {
+            let letter = match brush {
+                Cell::Block(block) => block.letter(),
+                Cell::Garbage => 'X',
+            };
+            let marker = if i == self.brush_index { "> " } else { "" };
+            brush_text.push_line(Line::from(vec![
+                span!("{}{} ", marker, letter),
+                span!("■").fg(brush.color()),
+            ]));
+        }
+        brush_text.push_line(Line::raw(""));
+        brush_text.push_line(Line::from(vec!["[space]".cyan(), " paint".dim()]));
+        brush_text.push_line(Line::from(vec!["[⌫]".cyan(), " erase".dim()]));
+        brush_text.push_line(Line::from(vec!["[tab]".cyan(), " queue/hold".dim()]));
+        brush_text.right_aligned().render(left_area, buf);
+
+        self.render_grid(board_area, buf);
+
+        let queue_line = if self.queue.is_empty() {
+            Line::from("random".dim())
+        } else {
+            Line::from(
+                self.queue
+                    .iter()
+                    .map(|&block| EditorWidget::block_span(block))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let hold_line = match self.hold {
+            Some(block) => Line::from(EditorWidget::block_span(block)),
+            None => Line::from("-".dim()),
+        };
+        let status_line = match &self.status {
+            Some(Ok(name)) => Line::from(span!("saved {}", name).green()),
+            Some(Err(_)) => Line::from("save failed".red()),
+            None => Line::raw(""),
+        };
+
+        let info_text = Text::from(vec![
+            Line::raw(""),
+            Line::raw(""),
+            self.heading("queue", EditorFocus::Queue),
+            queue_line,
+            Line::raw(""),
+            self.heading("hold", EditorFocus::Hold),
+            hold_line,
+            Line::raw(""),
+            Line::from(vec!["[enter]".cyan(), " test".dim()]),
+            Line::from(vec!["[ctrl+s]".cyan(), " save".dim()]),
+            Line::from(vec!["[esc]".cyan(), " back".dim()]),
+            Line::raw(""),
+            status_line,
+        ]);
+        info_text.left_aligned().render(right_area, buf);
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     buffer::Buffer,
@@ -8,9 +10,12 @@ use ratatui::{
     widgets::{Block, Clear, Widget},
 };
 
+use tetrus::constants::{DIG_ROWS, MASTER_LINES, SPRINT_LINES};
+
 use crate::{
     colors::{BRONZE, GOLD, SILVER},
-    scores::{HighScores, Initials, ScoreEntry},
+    scores::{HighScores, Initials, Leaderboard, ScoreEntry},
+    utils::duration_format::to_clock,
 };
 
 #[derive(Default, PartialEq, Eq)]
@@ -19,6 +24,7 @@ pub enum GameoverState {
     Pass,
     EnterGame,
     EnterMenu,
+    ExportFumen,
     Brake,
 }
 
@@ -31,19 +37,32 @@ pub enum GameoverStage {
 #[derive(PartialEq, Eq)]
 pub enum GameoverMode {
     Endless,
+    Sprint,
+    Blitz,
+    Dig,
+    Survival,
+    Marathon,
+    Master,
     LearnMoves,
+    Replay,
+    Ai,
+    Versus(Option<u8>),
+    Online(Option<bool>),
 }
 
 pub struct GameoverWidget<'a> {
     option_index: usize,
-    menu_options: [Span<'a>; 3],
+    menu_options: [Span<'a>; 4],
     stage: GameoverStage,
     mode: GameoverMode,
+    finished: bool,
     high_scores: HighScores,
-    game_mode_name: String,
+    leaderboard: Leaderboard,
     current_score: usize,
     current_lines: usize,
     current_level: usize,
+    current_time: Duration,
+    dig_rows: usize,
     qualified_rank: Option<usize>,
     highlighted_rank: Option<usize>,
     initials: Initials,
@@ -53,14 +72,22 @@ impl<'a> GameoverWidget<'a> {
     pub fn new() -> Self {
         Self {
             option_index: 0,
-            menu_options: ["again?".into(), "menu".into(), "quit".into()],
+            menu_options: [
+                "again?".into(),
+                "menu".into(),
+                "export fumen".into(),
+                "quit".into(),
+            ],
             stage: GameoverStage::Menu,
             mode: GameoverMode::Endless,
+            finished: false,
             high_scores: HighScores::default(),
-            game_mode_name: "endless".to_string(),
+            leaderboard: Leaderboard::Endless,
             current_score: 0,
             current_lines: 0,
             current_level: 0,
+            current_time: Duration::ZERO,
+            dig_rows: DIG_ROWS[0],
             qualified_rank: None,
             highlighted_rank: None,
             initials: Initials::new(),
@@ -69,27 +96,208 @@ impl<'a> GameoverWidget<'a> {
 
     pub fn setup_endless(&mut self, score: usize, lines: usize, level: usize) {
         self.mode = GameoverMode::Endless;
-        self.game_mode_name = "endless".to_string();
+        self.finished = false;
+        self.leaderboard = Leaderboard::Endless;
+        self.current_score = score;
+        self.current_lines = lines;
+        self.current_level = level;
+        self.current_time = Duration::ZERO;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_sprint(&mut self, finished: bool, time: Duration, lines: usize, level: usize) {
+        self.mode = GameoverMode::Sprint;
+        self.finished = finished;
+        self.leaderboard = Leaderboard::Sprint;
+        self.current_score = 0;
+        self.current_lines = lines;
+        self.current_level = level;
+        self.current_time = time;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_blitz(&mut self, finished: bool, score: usize, lines: usize, level: usize) {
+        self.mode = GameoverMode::Blitz;
+        self.finished = finished;
+        self.leaderboard = Leaderboard::Blitz;
         self.current_score = score;
         self.current_lines = lines;
         self.current_level = level;
+        self.current_time = Duration::ZERO;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_dig(
+        &mut self,
+        finished: bool,
+        rows: usize,
+        messiness: u32,
+        time: Duration,
+        garbage_cleared: usize,
+        level: usize,
+    ) {
+        self.mode = GameoverMode::Dig;
+        self.finished = finished;
+        self.leaderboard = Leaderboard::Dig { rows, messiness };
+        self.dig_rows = rows;
+        self.current_score = 0;
+        self.current_lines = garbage_cleared;
+        self.current_level = level;
+        self.current_time = time;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_survival(
+        &mut self,
+        accelerating: bool,
+        time: Duration,
+        garbage_cleared: usize,
+        level: usize,
+    ) {
+        self.mode = GameoverMode::Survival;
+        self.finished = false;
+        self.leaderboard = if accelerating {
+            Leaderboard::SurvivalRising
+        } else {
+            Leaderboard::Survival
+        };
+        self.current_score = 0;
+        self.current_lines = garbage_cleared;
+        self.current_level = level;
+        self.current_time = time;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_marathon(
+        &mut self,
+        finished: bool,
+        start_level: usize,
+        score: usize,
+        lines: usize,
+        level: usize,
+    ) {
+        self.mode = GameoverMode::Marathon;
+        self.finished = finished;
+        self.leaderboard = Leaderboard::Marathon(start_level);
+        self.current_score = score;
+        self.current_lines = lines;
+        self.current_level = level;
+        self.current_time = Duration::ZERO;
+        self.setup_leaderboard();
+    }
+
+    pub fn setup_master(&mut self, finished: bool, time: Duration, lines: usize, level: usize) {
+        self.mode = GameoverMode::Master;
+        self.finished = finished;
+        self.leaderboard = Leaderboard::Master;
+        self.current_score = 0;
+        self.current_lines = lines;
+        self.current_level = level;
+        self.current_time = time;
+        self.setup_leaderboard();
+    }
+
+    pub fn set_fumen_exported(&mut self) {
+        self.menu_options[2] = "fumen saved".into();
+    }
+
+    fn setup_leaderboard(&mut self) {
         self.option_index = 0;
+        self.menu_options[2] = "export fumen".into();
         self.initials = Initials::new();
         self.high_scores = HighScores::load();
-        self.qualified_rank = self.high_scores.check_qualification("endless", score);
+        self.highlighted_rank = None;
+
+        let can_qualify =
+            !matches!(self.mode, GameoverMode::Sprint | GameoverMode::Dig) || self.finished;
+        self.qualified_rank = if can_qualify {
+            self.high_scores
+                .check_qualification(self.leaderboard, &self.current_entry(Initials::new()))
+        } else {
+            None
+        };
 
         if self.qualified_rank.is_some() {
             self.stage = GameoverStage::EnteringInitials;
-            self.highlighted_rank = None;
         } else {
             self.stage = GameoverStage::Menu;
-            self.highlighted_rank = None;
         }
     }
 
-    pub fn setup_learn_moves(&mut self) {
-        self.mode = GameoverMode::LearnMoves;
+    fn current_entry(&self, initials: Initials) -> ScoreEntry {
+        ScoreEntry {
+            initials,
+            score: self.current_score,
+            lines: self.current_lines,
+            level: self.current_level,
+            time_ms: self.current_time.as_millis() as u64,
+        }
+    }
+
+    fn format_entry(&self, entry: &ScoreEntry) -> String {
+        match self.leaderboard {
+            Leaderboard::Endless | Leaderboard::Blitz | Leaderboard::Marathon(_) => {
+                GameoverWidget::format_number(entry.score)
+            }
+            Leaderboard::Sprint | Leaderboard::Dig { .. } => {
+                to_clock(&Duration::from_millis(entry.time_ms))
+            }
+            Leaderboard::Master => format!(
+                "{} {}",
+                entry.lines,
+                to_clock(&Duration::from_millis(entry.time_ms))
+            ),
+            Leaderboard::Survival | Leaderboard::SurvivalRising => format!(
+                "{} +{}",
+                to_clock(&Duration::from_millis(entry.time_ms)),
+                entry.lines
+            ),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match (self.finished, &self.mode) {
+            (_, GameoverMode::Replay) => "REPLAY OVER",
+            (_, GameoverMode::Versus(Some(1))) => "PLAYER 1 WINS",
+            (_, GameoverMode::Versus(Some(_))) => "PLAYER 2 WINS",
+            (_, GameoverMode::Versus(None)) => "DRAW",
+            (_, GameoverMode::Online(Some(true))) => "YOU WIN",
+            (_, GameoverMode::Online(Some(false))) => "YOU LOSE",
+            (_, GameoverMode::Online(None)) => "OPPONENT LEFT",
+            (true, GameoverMode::Blitz) => "TIME'S UP!",
+            (true, GameoverMode::LearnMoves) => "CLEARED!",
+            (true, GameoverMode::Marathon) => "CONGRATULATIONS!",
+            (true, _) => "FINISHED!",
+            (false, _) => "GAME OVER",
+        }
+    }
+
+    pub fn setup_learn_moves(&mut self, finished: bool) {
+        self.setup_without_scores(GameoverMode::LearnMoves);
+        self.finished = finished;
+    }
+
+    pub fn setup_replay(&mut self) {
+        self.setup_without_scores(GameoverMode::Replay);
+    }
+
+    pub fn setup_ai(&mut self) {
+        self.setup_without_scores(GameoverMode::Ai);
+    }
+
+    pub fn setup_versus(&mut self, winner: Option<u8>) {
+        self.setup_without_scores(GameoverMode::Versus(winner));
+    }
+
+    pub fn setup_online(&mut self, won: Option<bool>) {
+        self.setup_without_scores(GameoverMode::Online(won));
+    }
+
+    fn setup_without_scores(&mut self, mode: GameoverMode) {
+        self.mode = mode;
+        self.finished = false;
         self.option_index = 0;
+        self.menu_options[2] = "export fumen".into();
         self.stage = GameoverStage::Menu;
         self.qualified_rank = None;
         self.highlighted_rank = None;
@@ -112,15 +320,9 @@ impl<'a> GameoverWidget<'a> {
                     } else {
                         self.initials
                     };
-                    let rank = self.high_scores.insert(
-                        &self.game_mode_name,
-                        ScoreEntry {
-                            initials: final_initials,
-                            score: self.current_score,
-                            lines: self.current_lines,
-                            level: self.current_level,
-                        },
-                    );
+                    let rank = self
+                        .high_scores
+                        .insert(self.leaderboard, self.current_entry(final_initials));
                     self.highlighted_rank = Some(rank);
                     self.stage = GameoverStage::Menu;
                     GameoverState::Pass
@@ -141,7 +343,8 @@ impl<'a> GameoverWidget<'a> {
                     KeyCode::Enter | KeyCode::Char(' ') => match self.option_index {
                         0 => GameoverState::EnterGame,
                         1 => GameoverState::EnterMenu,
-                        2 => GameoverState::Brake,
+                        2 => GameoverState::ExportFumen,
+                        3 => GameoverState::Brake,
                         _ => unreachable!(),
                     },
                     _ => GameoverState::Pass,
@@ -156,7 +359,7 @@ impl<'a> GameoverWidget<'a> {
         let chars: Vec<char> = s.chars().collect();
         let len = chars.len();
         for (i, &c) in chars.iter().enumerate() {
-            if i > 0 && (len - i) % 3 == 0 {
+            if i > 0 && (len - i).is_multiple_of(3) {
                 result.push(',');
             }
             result.push(c);
@@ -167,14 +370,21 @@ impl<'a> GameoverWidget<'a> {
 
 impl<'a> Widget for &mut GameoverWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if self.mode == GameoverMode::LearnMoves {
+        if matches!(
+            self.mode,
+            GameoverMode::LearnMoves
+                | GameoverMode::Replay
+                | GameoverMode::Ai
+                | GameoverMode::Versus(_)
+                | GameoverMode::Online(_)
+        ) {
             let block_width = 30;
             let block_height = 9;
             let block_area =
                 area.centered(constraint!(== block_width), constraint!(== block_height));
 
             let mut lines = Vec::new();
-            lines.push(Line::from("GAME OVER".bold()).centered());
+            lines.push(Line::from(self.title().bold()).centered());
             lines.push(Line::raw(""));
 
             for (i, option) in self.menu_options.iter().enumerate() {
@@ -204,9 +414,11 @@ impl<'a> Widget for &mut GameoverWidget<'a> {
         let block_area = area.centered(constraint!(== block_width), constraint!(== block_height));
         let mut lines = Vec::new();
 
-        lines.push(Line::from("GAME OVER".bold()).centered());
+        lines.push(Line::from(self.title().bold()).centered());
         lines.push(Line::raw(""));
 
+        let current_entry = self.current_entry(Initials::new());
+
         match self.stage {
             GameoverStage::EnteringInitials => {
                 let rank_num = self.qualified_rank.unwrap_or(1);
@@ -231,8 +443,7 @@ impl<'a> Widget for &mut GameoverWidget<'a> {
                     .centered(),
                 );
 
-                lines
-                    .push(Line::from(GameoverWidget::format_number(self.current_score)).centered());
+                lines.push(Line::from(self.format_entry(&current_entry)).centered());
                 lines.push(Line::raw(""));
 
                 let mut slot_spans = Vec::new();
@@ -247,17 +458,56 @@ impl<'a> Widget for &mut GameoverWidget<'a> {
                 lines.push(Line::from(slot_spans).centered());
                 lines.push(Line::from("enter your initials".dim()).centered());
             }
-            GameoverStage::Menu => {
-                lines.push(Line::from(vec![span!("your score").white()]).centered());
-                lines
-                    .push(Line::from(GameoverWidget::format_number(self.current_score)).centered());
-            }
+            GameoverStage::Menu => match self.leaderboard {
+                Leaderboard::Sprint if !self.finished => {
+                    lines.push(Line::from(vec![span!("your lines").white()]).centered());
+                    lines.push(
+                        Line::from(format!("{}/{}", self.current_lines, SPRINT_LINES)).centered(),
+                    );
+                }
+                Leaderboard::Dig { .. } if !self.finished => {
+                    lines.push(Line::from(vec![span!("your garbage").white()]).centered());
+                    lines.push(
+                        Line::from(format!("{}/{}", self.current_lines, self.dig_rows)).centered(),
+                    );
+                }
+                Leaderboard::Sprint | Leaderboard::Dig { .. } => {
+                    lines.push(Line::from(vec![span!("your time").white()]).centered());
+                    lines.push(Line::from(self.format_entry(&current_entry)).centered());
+                }
+                Leaderboard::Master => {
+                    lines.push(Line::from(vec![span!("your lines").white()]).centered());
+                    lines.push(
+                        Line::from(format!("{}/{}", self.current_lines, MASTER_LINES)).centered(),
+                    );
+                    lines.push(Line::from(to_clock(&self.current_time).dim()).centered());
+                }
+                Leaderboard::Survival | Leaderboard::SurvivalRising => {
+                    lines.push(Line::from(vec![span!("you survived").white()]).centered());
+                    lines.push(Line::from(self.format_entry(&current_entry)).centered());
+                    lines.push(
+                        Line::from(format!("{} garbage cleared", self.current_lines).dim())
+                            .centered(),
+                    );
+                }
+                Leaderboard::Marathon(start_level) if self.finished => {
+                    lines.push(
+                        Line::from(format!("marathon cleared from lv{}", start_level).green())
+                            .centered(),
+                    );
+                    lines.push(Line::from(self.format_entry(&current_entry)).centered());
+                }
+                Leaderboard::Endless | Leaderboard::Blitz | Leaderboard::Marathon(_) => {
+                    lines.push(Line::from(vec![span!("your score").white()]).centered());
+                    lines.push(Line::from(self.format_entry(&current_entry)).centered());
+                }
+            },
         }
 
         lines.push(Line::raw(""));
         lines.push(Line::raw(""));
 
-        let top_5 = self.high_scores.get_top_5(&self.game_mode_name);
+        let top_5 = self.high_scores.get_top_5(self.leaderboard);
         for i in 0..5 {
             let rank_idx = i + 1;
             let is_highlighted = self.highlighted_rank == Some(rank_idx);
@@ -283,7 +533,7 @@ impl<'a> Widget for &mut GameoverWidget<'a> {
 
             if let Some(entry) = top_5.get(i) {
                 let initials_str = format!("{:<6}", entry.initials.to_string());
-                let score_str = format!("{:>10}", GameoverWidget::format_number(entry.score));
+                let score_str = format!("{:>10}", self.format_entry(entry));
 
                 if is_highlighted {
                     lines.push(
@@ -6,11 +6,13 @@ use ratatui::{
     widgets::Widget,
 };
 
-use crate::{
+use tetrus::{
     blocks::{Block, Rotation},
     board::Board,
 };
 
+use crate::colors::ToColor;
+
 pub struct HeldBlockWidget {
     held_block: Option<Block>,
     can_hold: bool,
@@ -40,11 +42,12 @@ impl Widget for &HeldBlockWidget {
             let start_x = area.right().saturating_sub(block_width);
             let start_y = area.y + 4;
 
-            for (block_x, block_y, color) in block.get_coordinates(Rotation::Deg0) {
+            let color = block.color();
+            for (block_x, block_y) in block.get_coordinates(Rotation::Deg0) {
                 let cell_x = start_x + (block_x * 2);
                 let cell_y = start_y + block_y;
 
-                if cell_x + 1 <= area.right() && cell_y < area.bottom() {
+                if cell_x < area.right() && cell_y < area.bottom() {
                     let style = if self.can_hold {
                         Style::default().fg(color)
                     } else {
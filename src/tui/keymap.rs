@@ -0,0 +1,63 @@
+use crossterm::event::KeyCode;
+
+use tetrus::action::GameAction;
+
+#[derive(Clone, Copy)]
+pub struct Keymap(&'static [(KeyCode, GameAction)]);
+
+impl Keymap {
+    pub const DEFAULT: Self = Self(&[
+        (KeyCode::Left, GameAction::MoveLeft),
+        (KeyCode::Right, GameAction::MoveRight),
+        (KeyCode::Down, GameAction::SoftDrop),
+        (KeyCode::Char(' '), GameAction::HardDrop),
+        (KeyCode::Up, GameAction::RotateCW),
+        (KeyCode::Char('x'), GameAction::RotateCW),
+        (KeyCode::Char('X'), GameAction::RotateCW),
+        (KeyCode::Char('z'), GameAction::RotateCCW),
+        (KeyCode::Char('Z'), GameAction::RotateCCW),
+        (KeyCode::Char('a'), GameAction::Rotate180),
+        (KeyCode::Char('A'), GameAction::Rotate180),
+        (KeyCode::Char('c'), GameAction::Hold),
+        (KeyCode::Char('C'), GameAction::Hold),
+        (KeyCode::Enter, GameAction::Pause),
+        (KeyCode::Char('p'), GameAction::Pause),
+        (KeyCode::Char('P'), GameAction::Pause),
+    ]);
+
+    pub const PLAYER_ONE: Self = Self(&[
+        (KeyCode::Char('a'), GameAction::MoveLeft),
+        (KeyCode::Char('A'), GameAction::MoveLeft),
+        (KeyCode::Char('d'), GameAction::MoveRight),
+        (KeyCode::Char('D'), GameAction::MoveRight),
+        (KeyCode::Char('s'), GameAction::SoftDrop),
+        (KeyCode::Char('S'), GameAction::SoftDrop),
+        (KeyCode::Char('w'), GameAction::HardDrop),
+        (KeyCode::Char('W'), GameAction::HardDrop),
+        (KeyCode::Char('e'), GameAction::RotateCW),
+        (KeyCode::Char('E'), GameAction::RotateCW),
+        (KeyCode::Char('q'), GameAction::RotateCCW),
+        (KeyCode::Char('Q'), GameAction::RotateCCW),
+        (KeyCode::Char('r'), GameAction::Rotate180),
+        (KeyCode::Char('R'), GameAction::Rotate180),
+        (KeyCode::Tab, GameAction::Hold),
+    ]);
+
+    pub const PLAYER_TWO: Self = Self(&[
+        (KeyCode::Left, GameAction::MoveLeft),
+        (KeyCode::Right, GameAction::MoveRight),
+        (KeyCode::Down, GameAction::SoftDrop),
+        (KeyCode::Up, GameAction::HardDrop),
+        (KeyCode::Char('2'), GameAction::RotateCW),
+        (KeyCode::Char('1'), GameAction::RotateCCW),
+        (KeyCode::Char('3'), GameAction::Rotate180),
+        (KeyCode::Char('0'), GameAction::Hold),
+    ]);
+
+    pub fn action(&self, code: KeyCode) -> Option<GameAction> {
+        self.0
+            .iter()
+            .find(|(key, _)| *key == code)
+            .map(|&(_, action)| action)
+    }
+}
@@ -8,12 +8,42 @@ use ratatui::{
     widgets::Widget,
 };
 
+use tetrus::{
+    board::GameGoal,
+    constants::{DIG_MESSINESS_STEP, DIG_ROWS, MARATHON_LINES, MAX_FALL_SPEED_LEVEL},
+    fumen,
+    garbage::MAX_MESSINESS,
+    preset::Preset,
+    replay::Replay,
+    timer::frames_to_duration,
+};
+
+use crate::{
+    presets, replays,
+    saved_game::SavedGame,
+    settings::{Handling, Settings},
+    tui::editor_widget::{EditorState, EditorWidget},
+    utils::duration_format::to_clock,
+};
+
+const MAX_FUMEN_LEN: usize = 8192;
+
 #[derive(Default, PartialEq, Eq)]
 pub enum MenuState {
     #[default]
     Pass,
+    Continue,
     EnterGame,
-    EnterGameWithPreset(crate::board::Grid, &'static [crate::blocks::Block], usize),
+    EnterMarathon(usize),
+    EnterMaster,
+    EnterSprint,
+    EnterBlitz,
+    EnterDig(usize, u32),
+    EnterSurvival(bool),
+    EnterVersus,
+    EnterGameWithPreset(Box<Preset>, usize),
+    WatchReplay(Box<Replay>, u32),
+    WatchAi,
     Brake,
 }
 
@@ -21,70 +51,365 @@ pub enum MenuState {
 pub enum MenuScreen {
     #[default]
     Main,
+    Marathon,
+    Dig,
+    Survival,
     LearnMoves,
+    Replay,
+    Handling,
+    Fumen,
+    Editor,
 }
 
 pub struct MenuWidget<'a> {
     title: Line<'a>,
     option_index: usize,
-    menu_options: [Span<'a>; 3],
+    menu_options: [Span<'a>; 14],
+    has_saved_game: bool,
     screen: MenuScreen,
+    marathon_index: usize,
+    start_level: usize,
+    dig_index: usize,
+    dig_rows: usize,
+    dig_messiness: u32,
+    survival_index: usize,
+    survival_accelerating: bool,
     learn_moves_index: usize,
     gravity: usize,
-    learn_options: [Span<'a>; 8],
+    learn_presets: Vec<Preset>,
+    learn_errors: Vec<String>,
+    fumen_input: String,
+    fumen_error: bool,
+    editor: EditorWidget,
+    replay: Option<Replay>,
+    replay_index: usize,
+    replay_speed: u32,
+    handling_index: usize,
+    notice: Option<String>,
+    pub settings: Settings,
 }
 
 impl<'a> MenuWidget<'a> {
     pub fn new(title: Line<'a>) -> Self {
+        let has_saved_game = SavedGame::exists();
+
         Self {
             title,
-            option_index: 0,
-            menu_options: ["endless".into(), "learn moves".into(), "quit".into()],
+            option_index: if has_saved_game { 0 } else { 1 },
+            menu_options: [
+                "continue".into(),
+                "endless".into(),
+                "marathon".into(),
+                "master".into(),
+                "40 lines".into(),
+                "blitz".into(),
+                "dig race".into(),
+                "survival".into(),
+                "versus".into(),
+                "learn moves".into(),
+                "watch replay".into(),
+                "watch ai".into(),
+                "handling".into(),
+                "quit".into(),
+            ],
+            has_saved_game,
             screen: MenuScreen::Main,
+            marathon_index: 1,
+            start_level: 1,
+            dig_index: 2,
+            dig_rows: DIG_ROWS[0],
+            dig_messiness: 30,
+            survival_index: 1,
+            survival_accelerating: true,
             learn_moves_index: 1,
             gravity: 1,
-            learn_options: [
-                "T-Spin Double Setup".into(),
-                "T-Spin Triple Setup".into(),
-                "Quad Clear Setup".into(),
-                "L-Spin Setup".into(),
-                "J-Spin Setup".into(),
-                "S-Spin Setup".into(),
-                "Z-Spin Setup".into(),
-                "[←] back".into(),
-            ],
+            learn_presets: Vec::new(),
+            learn_errors: Vec::new(),
+            fumen_input: String::new(),
+            fumen_error: false,
+            editor: EditorWidget::new(),
+            replay: None,
+            replay_index: 0,
+            replay_speed: 1,
+            handling_index: 0,
+            notice: None,
+            settings: Settings::load(),
+        }
+    }
+
+    pub fn set_has_saved_game(&mut self, has_saved_game: bool) {
+        self.has_saved_game = has_saved_game;
+        self.option_index = if has_saved_game { 0 } else { 1 };
+    }
+
+    pub fn set_notice(&mut self, notice: String) {
+        self.notice = Some(notice);
+    }
+
+    fn first_option_index(&self) -> usize {
+        if self.has_saved_game {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn leave_handling(&mut self) {
+        self.screen = MenuScreen::Main;
+        let _ = self.settings.save();
+    }
+
+    fn adjust_handling(&mut self, increase: bool) {
+        let handling = &mut self.settings.handling;
+        let (value, min, max) = match self.handling_index {
+            0 => (&mut handling.das_frames, 0, Handling::MAX_DAS_FRAMES),
+            1 => (&mut handling.arr_frames, 0, Handling::MAX_ARR_FRAMES),
+            2 => (
+                &mut handling.soft_drop_factor,
+                1,
+                Handling::MAX_SOFT_DROP_FACTOR,
+            ),
+            _ => return,
+        };
+
+        if increase {
+            *value = (*value + 1).min(max);
+        } else {
+            *value = value.saturating_sub(1).max(min);
+        }
+    }
+
+    fn handling_text(&self, index: usize) -> String {
+        let handling = &self.settings.handling;
+        match index {
+            0 => format!(
+                "das {}f ({}ms)",
+                handling.das_frames,
+                handling.das().as_millis()
+            ),
+            1 => format!(
+                "arr {}f ({}ms)",
+                handling.arr_frames,
+                handling.arr().as_millis()
+            ),
+            _ => format!("soft drop {}x", handling.soft_drop_factor),
+        }
+    }
+
+    fn replay_text(replay: &Replay) -> String {
+        let mode = match replay.goal {
+            GameGoal::None => "endless".to_string(),
+            GameGoal::Lines(lines) => format!("{} lines", lines),
+            GameGoal::Time(_) => "blitz".to_string(),
+            GameGoal::Dig { rows, .. } => format!("dig {}", rows),
+            GameGoal::Survival { .. } => "survival".to_string(),
+            GameGoal::Marathon { start_level, .. } => format!("marathon lv{}", start_level),
+            GameGoal::Master => "master".to_string(),
+        };
+        let duration = frames_to_duration(replay.frames);
+        format!("{} {}", mode, to_clock(&duration))
+    }
+
+    fn back_line(is_selected: bool) -> Line<'a> {
+        if is_selected {
+            Line::from(vec![
+                span!("- ").green().bold(),
+                span!("[←]").cyan().bold(),
+                span!(" back -").green().bold(),
+            ])
+            .centered()
+        } else {
+            Line::from(vec![span!("[←]").cyan(), span!(" back")]).centered()
         }
     }
 
     pub fn handle_key_event(&mut self, event: KeyEvent) -> MenuState {
+        self.notice = None;
         match self.screen {
             MenuScreen::Main => {
-                let options_len = self.menu_options.len();
+                let first = self.first_option_index();
+                let options_len = self.menu_options.len() - first;
                 match event.code {
                     KeyCode::Up => {
-                        self.option_index = (self.option_index + options_len - 1) % options_len;
+                        self.option_index =
+                            first + (self.option_index - first + options_len - 1) % options_len;
                         MenuState::Pass
                     }
                     KeyCode::Down => {
-                        self.option_index = (self.option_index + 1) % options_len;
+                        self.option_index = first + (self.option_index - first + 1) % options_len;
                         MenuState::Pass
                     }
                     KeyCode::Enter | KeyCode::Char(' ') => match self.option_index {
-                        0 => MenuState::EnterGame,
-                        1 => {
+                        0 => MenuState::Continue,
+                        1 => MenuState::EnterGame,
+                        2 => {
+                            self.screen = MenuScreen::Marathon;
+                            self.marathon_index = 1;
+                            MenuState::Pass
+                        }
+                        3 => MenuState::EnterMaster,
+                        4 => MenuState::EnterSprint,
+                        5 => MenuState::EnterBlitz,
+                        6 => {
+                            self.screen = MenuScreen::Dig;
+                            self.dig_index = 2;
+                            MenuState::Pass
+                        }
+                        7 => {
+                            self.screen = MenuScreen::Survival;
+                            self.survival_index = 1;
+                            MenuState::Pass
+                        }
+                        8 => MenuState::EnterVersus,
+                        9 => {
                             self.screen = MenuScreen::LearnMoves;
+                            (self.learn_presets, self.learn_errors) = presets::load();
                             self.learn_moves_index = 1;
                             MenuState::Pass
                         }
-                        2 => MenuState::Brake,
+                        10 => {
+                            self.screen = MenuScreen::Replay;
+                            self.replay = replays::load();
+                            self.replay_index = if self.replay.is_some() { 1 } else { 2 };
+                            MenuState::Pass
+                        }
+                        11 => MenuState::WatchAi,
+                        12 => {
+                            self.screen = MenuScreen::Handling;
+                            self.handling_index = 0;
+                            MenuState::Pass
+                        }
+                        13 => MenuState::Brake,
                         _ => unreachable!(),
                     },
                     KeyCode::Esc => MenuState::Brake,
                     _ => MenuState::Pass,
                 }
             }
+            MenuScreen::Marathon => {
+                const MARATHON_OPTIONS_LEN: usize = 3;
+                match event.code {
+                    KeyCode::Up => {
+                        self.marathon_index =
+                            (self.marathon_index + MARATHON_OPTIONS_LEN - 1) % MARATHON_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Down => {
+                        self.marathon_index = (self.marathon_index + 1) % MARATHON_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left if self.marathon_index == 0 => {
+                        if self.start_level > 1 {
+                            self.start_level -= 1;
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Right if self.marathon_index == 0 => {
+                        if self.start_level < MAX_FALL_SPEED_LEVEL {
+                            self.start_level += 1;
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Left | KeyCode::Esc => {
+                        self.screen = MenuScreen::Main;
+                        MenuState::Pass
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => match self.marathon_index {
+                        1 => MenuState::EnterMarathon(self.start_level),
+                        2 => {
+                            self.screen = MenuScreen::Main;
+                            MenuState::Pass
+                        }
+                        _ => MenuState::Pass,
+                    },
+                    _ => MenuState::Pass,
+                }
+            }
+            MenuScreen::Dig => {
+                const DIG_OPTIONS_LEN: usize = 4;
+                match event.code {
+                    KeyCode::Up => {
+                        self.dig_index = (self.dig_index + DIG_OPTIONS_LEN - 1) % DIG_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Down => {
+                        self.dig_index = (self.dig_index + 1) % DIG_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        let increase = event.code == KeyCode::Right;
+                        match self.dig_index {
+                            0 => {
+                                let index = DIG_ROWS.iter().position(|&r| r == self.dig_rows);
+                                let next = if increase {
+                                    index.map_or(0, |i| (i + 1).min(DIG_ROWS.len() - 1))
+                                } else {
+                                    index.map_or(0, |i| i.saturating_sub(1))
+                                };
+                                self.dig_rows = DIG_ROWS[next];
+                            }
+                            1 if increase => {
+                                self.dig_messiness =
+                                    (self.dig_messiness + DIG_MESSINESS_STEP).min(MAX_MESSINESS);
+                            }
+                            1 => {
+                                self.dig_messiness =
+                                    self.dig_messiness.saturating_sub(DIG_MESSINESS_STEP);
+                            }
+                            _ if !increase => self.screen = MenuScreen::Main,
+                            _ => (),
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Esc => {
+                        self.screen = MenuScreen::Main;
+                        MenuState::Pass
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => match self.dig_index {
+                        2 => MenuState::EnterDig(self.dig_rows, self.dig_messiness),
+                        3 => {
+                            self.screen = MenuScreen::Main;
+                            MenuState::Pass
+                        }
+                        _ => MenuState::Pass,
+                    },
+                    _ => MenuState::Pass,
+                }
+            }
+            MenuScreen::Survival => {
+                const SURVIVAL_OPTIONS_LEN: usize = 3;
+                match event.code {
+                    KeyCode::Up => {
+                        self.survival_index =
+                            (self.survival_index + SURVIVAL_OPTIONS_LEN - 1) % SURVIVAL_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Down => {
+                        self.survival_index = (self.survival_index + 1) % SURVIVAL_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left | KeyCode::Right if self.survival_index == 0 => {
+                        self.survival_accelerating = !self.survival_accelerating;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left | KeyCode::Esc => {
+                        self.screen = MenuScreen::Main;
+                        MenuState::Pass
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => match self.survival_index {
+                        1 => MenuState::EnterSurvival(self.survival_accelerating),
+                        2 => {
+                            self.screen = MenuScreen::Main;
+                            MenuState::Pass
+                        }
+                        _ => MenuState::Pass,
+                    },
+                    _ => MenuState::Pass,
+                }
+            }
             MenuScreen::LearnMoves => {
-                let total_len = 1 + self.learn_options.len();
+                let presets_len = self.learn_presets.len();
+                let total_len = presets_len + 4;
                 match event.code {
                     KeyCode::Up => {
                         self.learn_moves_index =
@@ -118,59 +443,177 @@ impl<'a> MenuWidget<'a> {
                     }
                     KeyCode::Enter | KeyCode::Char(' ') => match self.learn_moves_index {
                         0 => MenuState::Pass,
-                        1 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::t_spin_double(),
-                            &[crate::blocks::Block::T],
-                            self.gravity,
-                        ),
-                        2 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::t_spin_triple(),
-                            &[crate::blocks::Block::T],
-                            self.gravity,
-                        ),
-                        3 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::quad_clear(),
-                            &[crate::blocks::Block::Line],
-                            self.gravity,
-                        ),
-                        4 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::l_spin(),
-                            &[crate::blocks::Block::L],
-                            self.gravity,
-                        ),
-                        5 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::j_spin(),
-                            &[crate::blocks::Block::J],
-                            self.gravity,
-                        ),
-                        6 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::s_spin(),
-                            &[crate::blocks::Block::S],
-                            self.gravity,
-                        ),
-                        7 => MenuState::EnterGameWithPreset(
-                            crate::board::presets::z_spin(),
-                            &[crate::blocks::Block::Z],
-                            self.gravity,
-                        ),
-                        8 => {
+                        i if i <= presets_len => {
+                            let preset = &self.learn_presets[i - 1];
+                            MenuState::EnterGameWithPreset(
+                                Box::new(preset.clone()),
+                                preset.gravity.unwrap_or(self.gravity),
+                            )
+                        }
+                        i if i == presets_len + 1 => {
+                            self.screen = MenuScreen::Fumen;
+                            self.fumen_input.clear();
+                            self.fumen_error = false;
+                            MenuState::Pass
+                        }
+                        i if i == presets_len + 2 => {
+                            self.screen = MenuScreen::Editor;
+                            MenuState::Pass
+                        }
+                        _ => {
+                            self.screen = MenuScreen::Main;
+                            MenuState::Pass
+                        }
+                    },
+                    _ => MenuState::Pass,
+                }
+            }
+            MenuScreen::Replay => {
+                const REPLAY_OPTIONS_LEN: usize = 3;
+                match event.code {
+                    KeyCode::Up if self.replay.is_some() => {
+                        self.replay_index =
+                            (self.replay_index + REPLAY_OPTIONS_LEN - 1) % REPLAY_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Down if self.replay.is_some() => {
+                        self.replay_index = (self.replay_index + 1) % REPLAY_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left => {
+                        if self.replay_index == 0 {
+                            self.replay_speed = (self.replay_speed / 2).max(1);
+                        } else {
+                            self.screen = MenuScreen::Main;
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Right => {
+                        if self.replay_index == 0 {
+                            self.replay_speed = (self.replay_speed * 2).min(8);
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Esc => {
+                        self.screen = MenuScreen::Main;
+                        MenuState::Pass
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => match self.replay_index {
+                        0 => MenuState::Pass,
+                        1 => match &self.replay {
+                            Some(replay) => {
+                                MenuState::WatchReplay(Box::new(replay.clone()), self.replay_speed)
+                            }
+                            None => MenuState::Pass,
+                        },
+                        _ => {
                             self.screen = MenuScreen::Main;
                             MenuState::Pass
                         }
-                        _ => unreachable!(),
                     },
                     _ => MenuState::Pass,
                 }
             }
+            MenuScreen::Editor => match self.editor.handle_key_event(event) {
+                EditorState::Test(preset) => MenuState::EnterGameWithPreset(preset, self.gravity),
+                EditorState::Back => {
+                    self.screen = MenuScreen::LearnMoves;
+                    (self.learn_presets, self.learn_errors) = presets::load();
+                    MenuState::Pass
+                }
+                EditorState::Pass => MenuState::Pass,
+            },
+            MenuScreen::Fumen => match event.code {
+                KeyCode::Esc | KeyCode::Left => {
+                    self.screen = MenuScreen::LearnMoves;
+                    MenuState::Pass
+                }
+                KeyCode::Backspace => {
+                    self.fumen_input.pop();
+                    self.fumen_error = false;
+                    MenuState::Pass
+                }
+                KeyCode::Char(c) if self.fumen_input.len() < MAX_FUMEN_LEN => {
+                    self.fumen_input.push(c);
+                    self.fumen_error = false;
+                    MenuState::Pass
+                }
+                KeyCode::Enter => match fumen::decode(&self.fumen_input) {
+                    Some(pages) => {
+                        let pieces = pages
+                            .iter()
+                            .filter_map(|page| page.operation.map(|operation| operation.block))
+                            .collect();
+                        let preset = Preset {
+                            title: "fumen".to_string(),
+                            description: String::new(),
+                            grid: pages[0].field,
+                            pieces,
+                            hold: None,
+                            gravity: None,
+                            objective: None,
+                        };
+                        MenuState::EnterGameWithPreset(Box::new(preset), self.gravity)
+                    }
+                    None => {
+                        self.fumen_error = !self.fumen_input.is_empty();
+                        MenuState::Pass
+                    }
+                },
+                _ => MenuState::Pass,
+            },
+            MenuScreen::Handling => {
+                const HANDLING_OPTIONS_LEN: usize = 4;
+                match event.code {
+                    KeyCode::Up => {
+                        self.handling_index =
+                            (self.handling_index + HANDLING_OPTIONS_LEN - 1) % HANDLING_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Down => {
+                        self.handling_index = (self.handling_index + 1) % HANDLING_OPTIONS_LEN;
+                        MenuState::Pass
+                    }
+                    KeyCode::Left => {
+                        if self.handling_index == HANDLING_OPTIONS_LEN - 1 {
+                            self.leave_handling();
+                        } else {
+                            self.adjust_handling(false);
+                        }
+                        MenuState::Pass
+                    }
+                    KeyCode::Right => {
+                        self.adjust_handling(true);
+                        MenuState::Pass
+                    }
+                    KeyCode::Esc => {
+                        self.leave_handling();
+                        MenuState::Pass
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => {
+                        if self.handling_index == HANDLING_OPTIONS_LEN - 1 {
+                            self.leave_handling();
+                        }
+                        MenuState::Pass
+                    }
+                    _ => MenuState::Pass,
+                }
+            }
         }
     }
 }
 
 impl<'a> Widget for &mut MenuWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.screen == MenuScreen::Editor {
+            self.editor.render(area, buf);
+            return;
+        }
+
         if self.screen == MenuScreen::LearnMoves {
-            let max_visible = (area.height.saturating_sub(6) as usize).max(4);
-            let total_options = 1 + self.learn_options.len();
+            let presets_len = self.learn_presets.len();
+            let max_visible = (area.height.saturating_sub(10) as usize).max(4);
+            let total_options = presets_len + 4;
             let scroll_offset = if self.learn_moves_index >= max_visible {
                 self.learn_moves_index - max_visible + 1
             } else {
@@ -189,50 +632,223 @@ impl<'a> Widget for &mut MenuWidget<'a> {
 
             for i in scroll_offset..end_offset {
                 let is_selected = i == self.learn_moves_index;
+                if i == total_options - 1 {
+                    menu_text.push_line(MenuWidget::back_line(is_selected));
+                    continue;
+                }
+
+                let text = match i {
+                    0 => format!("gravity {}", self.gravity),
+                    i if i <= presets_len => self.learn_presets[i - 1].title.clone(),
+                    i if i == presets_len + 1 => "Paste Fumen".to_string(),
+                    _ => "Board Editor".to_string(),
+                };
+                if is_selected {
+                    menu_text.push_line(span!("- {} -", text).green().bold());
+                } else {
+                    menu_text.push_line(Line::from(text));
+                }
                 if i == 0 {
-                    let text = format!("gravity {}", self.gravity);
-                    if is_selected {
-                        menu_text.push_line(span!("- {} -", text).green().bold());
-                    } else {
-                        menu_text.push_line(Line::from(text));
-                    }
                     menu_text.push_line(Line::raw(""));
+                }
+            }
+
+            if end_offset < total_options {
+                menu_text.push_line(Line::from("▼".dim()).centered());
+            }
+
+            let preset = match self.learn_moves_index {
+                i if (1..=presets_len).contains(&i) => Some(&self.learn_presets[i - 1]),
+                _ => None,
+            };
+            let description = preset.map_or("", |preset| preset.description.as_str());
+            let goal = preset
+                .and_then(|preset| preset.objective)
+                .map_or(String::new(), |objective| {
+                    format!("goal: {}", objective.name())
+                });
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from(description.dim()));
+            menu_text.push_line(Line::from(goal.dim()));
+            for error in &self.learn_errors {
+                menu_text.push_line(Line::from(error.as_str().red()));
+            }
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Marathon {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("MARATHON".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from(format!("clear {} lines", MARATHON_LINES).dim()));
+            menu_text.push_line(Line::raw(""));
+
+            let options = [
+                format!("start level {}", self.start_level),
+                "start".to_string(),
+            ];
+            for (i, text) in options.into_iter().enumerate() {
+                if i == self.marathon_index {
+                    menu_text.push_line(span!("- {} -", text).green().bold());
                 } else {
-                    let option_idx = i - 1;
-                    if option_idx == 7 {
-                        if is_selected {
-                            menu_text.push_line(
-                                Line::from(vec![
-                                    span!("- ").green().bold(),
-                                    span!("[←]").cyan().bold(),
-                                    span!(" back -").green().bold(),
-                                ])
-                                .centered(),
-                            );
-                        } else {
-                            menu_text.push_line(
-                                Line::from(vec![
-                                    span!("[←]").cyan(),
-                                    span!(" back"),
-                                ])
-                                .centered(),
-                            );
-                        }
-                    } else {
-                        let option = &self.learn_options[option_idx];
-                        if is_selected {
-                            menu_text.push_line(span!("- {} -", option).green().bold());
+                    menu_text.push_line(Line::from(text));
+                }
+            }
+
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(self.marathon_index == 2));
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Dig {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("DIG RACE".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("clear every garbage row".dim()));
+            menu_text.push_line(Line::raw(""));
+
+            let options = [
+                format!("rows {}", self.dig_rows),
+                format!("messiness {}%", self.dig_messiness),
+                "start".to_string(),
+            ];
+            for (i, text) in options.into_iter().enumerate() {
+                if i == self.dig_index {
+                    menu_text.push_line(span!("- {} -", text).green().bold());
+                } else {
+                    menu_text.push_line(Line::from(text));
+                }
+            }
+
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(self.dig_index == 3));
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Survival {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("SURVIVAL".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("outlast the rising garbage".dim()));
+            menu_text.push_line(Line::raw(""));
+
+            let pace = if self.survival_accelerating {
+                "rising"
+            } else {
+                "fixed"
+            };
+            let options = [format!("pace {}", pace), "start".to_string()];
+            for (i, text) in options.into_iter().enumerate() {
+                if i == self.survival_index {
+                    menu_text.push_line(span!("- {} -", text).green().bold());
+                } else {
+                    menu_text.push_line(Line::from(text));
+                }
+            }
+
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(self.survival_index == 2));
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Replay {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("WATCH REPLAY".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+
+            match &self.replay {
+                Some(replay) => {
+                    menu_text.push_line(Line::from(MenuWidget::replay_text(replay).dim()));
+                    menu_text.push_line(Line::raw(""));
+
+                    let options = [format!("speed {}x", self.replay_speed), "watch".to_string()];
+                    for (i, text) in options.into_iter().enumerate() {
+                        if i == self.replay_index {
+                            menu_text.push_line(span!("- {} -", text).green().bold());
                         } else {
-                            menu_text.push_line(option.to_span());
+                            menu_text.push_line(Line::from(text));
                         }
                     }
                 }
+                None => menu_text.push_line(Line::from("no replay saved yet".dim())),
             }
 
-            if end_offset < total_options {
-                menu_text.push_line(Line::from("▼".dim()).centered());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(self.replay_index == 2));
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Fumen {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("PASTE FUMEN".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+
+            let visible_len = area.width.saturating_sub(6) as usize;
+            let skipped = self.fumen_input.chars().count().saturating_sub(visible_len);
+            let visible: String = self.fumen_input.chars().skip(skipped).collect();
+            menu_text.push_line(Line::from(vec![
+                span!("{}", visible).white(),
+                span!("_").green(),
+            ]));
+
+            if self.fumen_error {
+                menu_text.push_line(Line::from("could not read this fumen".red()));
+            } else {
+                menu_text.push_line(Line::from("paste a v115@ fumen and press enter".dim()));
+            }
+
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(false));
+
+            let lines_count = menu_text.lines.len() as u16;
+            let centered_area = area.centered_vertically(constraint!(== lines_count));
+            menu_text.centered().render(centered_area, buf);
+            return;
+        }
+
+        if self.screen == MenuScreen::Handling {
+            let mut menu_text = Text::from(self.title.clone());
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from("HANDLING".bold()).centered());
+            menu_text.push_line(Line::raw(""));
+
+            for i in 0..3 {
+                let text = self.handling_text(i);
+                if i == self.handling_index {
+                    menu_text.push_line(span!("- {} -", text).green().bold());
+                } else {
+                    menu_text.push_line(Line::from(text));
+                }
             }
 
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(MenuWidget::back_line(self.handling_index == 3));
+
             let lines_count = menu_text.lines.len() as u16;
             let centered_area = area.centered_vertically(constraint!(== lines_count));
             menu_text.centered().render(centered_area, buf);
@@ -242,7 +858,12 @@ impl<'a> Widget for &mut MenuWidget<'a> {
         let mut menu_text = Text::from(self.title.clone());
         menu_text.push_line(Line::raw(""));
 
-        for (i, option) in self.menu_options.iter().enumerate() {
+        for (i, option) in self
+            .menu_options
+            .iter()
+            .enumerate()
+            .skip(self.first_option_index())
+        {
             if i == self.option_index {
                 menu_text.push_line(span!("- {} -", option).green().bold());
             } else {
@@ -250,6 +871,11 @@ impl<'a> Widget for &mut MenuWidget<'a> {
             }
         }
 
+        if let Some(notice) = &self.notice {
+            menu_text.push_line(Line::raw(""));
+            menu_text.push_line(Line::from(notice.as_str().red()));
+        }
+
         let lines_count = menu_text.lines.len() as u16;
         let centered_area = area.centered_vertically(constraint!(== lines_count));
         menu_text.centered().render(centered_area, buf);
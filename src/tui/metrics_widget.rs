@@ -1,7 +1,11 @@
-use crate::{
-    board::Board,
-    constants::GOAL_MULTIPLIER,
-    utils::integer_format::{to_superscript, to_superscript_with_separator},
+use tetrus::{
+    board::{Board, GameGoal},
+    constants::{GOAL_MULTIPLIER, MASTER_LINES},
+};
+
+use crate::utils::{
+    duration_format::write_clock,
+    integer_format::{to_superscript, to_superscript_with_separator},
 };
 use ratatui::{
     buffer::Buffer,
@@ -9,12 +13,13 @@ use ratatui::{
     macros::{line, span, text},
     widgets::Widget,
 };
-use std::{fmt::Write, time::Duration};
+use std::time::Duration;
 
 pub struct MetricsWidget {
     time: String,
     level: usize,
     cleaned_lines: usize,
+    lines_goal: usize,
     score: usize,
 }
 const MAX_INSTANT_STR_CAPACITY: usize = 9;
@@ -25,30 +30,37 @@ impl MetricsWidget {
             time: String::with_capacity(MAX_INSTANT_STR_CAPACITY),
             level: 1,
             cleaned_lines: 0,
+            lines_goal: GOAL_MULTIPLIER,
             score: 0,
         }
     }
 
     pub fn format_instant(&mut self, instant: &Duration) {
-        let total_ms = instant.as_millis();
-
-        let minutes = (total_ms / 60_000) % 60;
-        let seconds = (total_ms / 1_000) % 60;
-        let milliseconds = total_ms % 1_000;
-
         self.time.clear();
-
-        let _ = write!(
-            self.time,
-            "{:02}:{:02}.{:03}",
-            minutes, seconds, milliseconds
-        );
+        let _ = write_clock(&mut self.time, instant);
     }
 
     pub fn copy_metrics(&mut self, board: &Board) {
-        self.format_instant(&board.timer.elapsed());
+        match board.goal {
+            GameGoal::Time(duration) => {
+                self.format_instant(&duration.saturating_sub(board.timer.elapsed()))
+            }
+            _ => self.format_instant(&board.timer.elapsed()),
+        }
         self.level = board.stats.level;
-        self.cleaned_lines = board.stats.cleaned_lines;
+        self.cleaned_lines = match board.goal {
+            GameGoal::Dig { .. } => board.stats.garbage_cleared,
+            _ => board.stats.cleaned_lines,
+        };
+        self.lines_goal = match board.goal {
+            GameGoal::Lines(lines)
+            | GameGoal::Dig { rows: lines, .. }
+            | GameGoal::Marathon { lines, .. } => lines,
+            GameGoal::Master => MASTER_LINES,
+            GameGoal::None | GameGoal::Time(_) | GameGoal::Survival { .. } => {
+                board.next_level_lines()
+            }
+        };
         self.score = board.stats.score;
     }
 }
@@ -64,7 +76,7 @@ impl Widget for &MetricsWidget {
             line![span!(
                 "{}⁄{}",
                 to_superscript(self.cleaned_lines),
-                to_superscript(self.level * GOAL_MULTIPLIER)
+                to_superscript(self.lines_goal)
             )],
             "time",
             self.time.as_str(),
@@ -9,14 +9,16 @@ use ratatui::{
 };
 use tachyonfx::{fx, EffectRenderer, Interpolation};
 
-use crate::{
+use tetrus::{
     board::Board,
-    colors::GOLD,
+    clear::{ClearKind, Spin},
     constants::{COMBO_NOTIFICATION_DURATION, COMBO_NOTIFICATION_FADE_DELAY},
 };
 
+use crate::colors::{ToColor, GOLD};
+
 pub struct MovementWidget {
-    last_movement: Option<(&'static str, usize, Duration)>,
+    last_movement: Option<(ClearKind, Duration)>,
     combo: Option<(usize, Duration)>,
 }
 
@@ -41,28 +43,23 @@ impl MovementWidget {
             .saturating_sub(COMBO_NOTIFICATION_FADE_DELAY)
             .as_millis() as u32;
 
-        if let Some((movement, b2b_count, elapsed)) = self.last_movement {
-            let has_b2b = movement.starts_with("B2B ");
-            let rest = movement.strip_prefix("B2B ").unwrap_or(movement);
-            let has_tspin = rest.contains("T-Spin");
-
-            if has_tspin {
-                let text = Line::from("T-SPIN").magenta().bold().right_aligned();
+        if let Some((clear, elapsed)) = self.last_movement {
+            if let Some(Spin { block, mini }) = clear.spin {
+                let label = if mini { " MINI" } else { "" };
+                let text = Line::from(format!("{}-SPIN{}", block.letter(), label))
+                    .fg(block.color())
+                    .bold()
+                    .right_aligned();
                 frame.render_widget(text, tspin_area);
             }
 
-            let clear_text = if rest.contains("Single") || rest.contains("single") {
-                Some("SINGLE")
-            } else if rest.contains("Double") || rest.contains("double") {
-                Some("DOUBLE")
-            } else if rest.contains("Triple") || rest.contains("triple") {
-                Some("TRIPLE")
-            } else if rest.contains("Quad") || rest.contains("quad") {
-                Some("QUAD")
-            } else if rest.contains("Perfect Clear") {
-                Some("PERFECT CLEAR!")
-            } else {
-                None
+            let clear_text = match (clear.perfect_clear, clear.lines) {
+                (true, _) => Some("PERFECT CLEAR!"),
+                (false, 1) => Some("SINGLE"),
+                (false, 2) => Some("DOUBLE"),
+                (false, 3) => Some("TRIPLE"),
+                (false, 4) => Some("QUAD"),
+                _ => None,
             };
 
             if let Some(clear_str) = clear_text {
@@ -70,8 +67,8 @@ impl MovementWidget {
                 frame.render_widget(text, clear_area);
             }
 
-            if has_b2b {
-                let text = Line::from(format!("B2B  x{}", b2b_count.max(1)))
+            if clear.is_b2b() {
+                let text = Line::from(format!("B2B  x{}", clear.b2b))
                     .fg(GOLD)
                     .bold()
                     .right_aligned();
@@ -85,13 +82,13 @@ impl MovementWidget {
                     (fade_duration, Interpolation::CubicOut),
                 );
 
-                if has_tspin {
+                if clear.spin.is_some() {
                     frame.render_effect(&mut effect, tspin_area, effect_elapsed.into());
                 }
                 if clear_text.is_some() {
                     frame.render_effect(&mut effect, clear_area, effect_elapsed.into());
                 }
-                if has_b2b {
+                if clear.is_b2b() {
                     frame.render_effect(&mut effect, b2b_area, effect_elapsed.into());
                 }
             }
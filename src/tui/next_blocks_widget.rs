@@ -1,10 +1,12 @@
 use ratatui::{buffer::Buffer, layout::Rect, macros::text, style::Style, widgets::Widget};
 
-use crate::{
+use tetrus::{
     blocks::{Block, Rotation},
     blocks_manager::BlocksManager,
 };
 
+use crate::colors::ToColor;
+
 pub struct NextBlocksWidget {
     next_blocks: [Block; 5],
 }
@@ -30,7 +32,8 @@ impl Widget for &NextBlocksWidget {
             let start_x = area.x;
             let start_y = area.y + 4 + (i as u16 * 3);
 
-            for (bx, by, color) in block.get_coordinates(Rotation::Deg0) {
+            let color = block.color();
+            for (bx, by) in block.get_coordinates(Rotation::Deg0) {
                 let cell_x = start_x + (bx * 2);
                 let cell_y = start_y + by;
 
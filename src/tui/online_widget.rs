@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    macros::{line, span, vertical},
+    style::Stylize,
+    text::Line,
+    widgets::Widget,
+};
+
+use tetrus::{
+    board::{grid_from_str, Board, Grid},
+    constants::{COLUMNS, ROWS},
+    netplay::{field_of, NetMessage, NetPeer},
+};
+
+use crate::tui::board_view::MiniBoardView;
+
+pub enum OnlineEvent {
+    Attack(usize),
+    OpponentToppedOut,
+}
+
+pub enum RoundEvent {
+    Start(u64, u32),
+    Rematch,
+}
+
+pub struct OnlineWidget {
+    peer: NetPeer,
+    round: u32,
+    inbox: VecDeque<NetMessage>,
+    last_meter: usize,
+    opponent_field: Grid,
+    opponent_incoming: usize,
+    last_field: String,
+}
+
+impl OnlineWidget {
+    pub fn new(peer: NetPeer) -> Self {
+        Self {
+            peer,
+            round: 0,
+            inbox: VecDeque::new(),
+            last_meter: 0,
+            opponent_field: [[None; COLUMNS as usize]; ROWS as usize],
+            opponent_incoming: 0,
+            last_field: String::new(),
+        }
+    }
+
+    pub fn start_round(&mut self, round: u32) {
+        self.round = round;
+        self.last_meter = 0;
+        self.opponent_field = [[None; COLUMNS as usize]; ROWS as usize];
+        self.opponent_incoming = 0;
+        self.last_field.clear();
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.peer.is_host()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.peer.is_closed()
+    }
+
+    fn receive(&mut self) {
+        while let Some(message) = self.peer.try_recv() {
+            self.inbox.push_back(message);
+        }
+    }
+
+    fn is_pending_round_event(&self, message: &NetMessage) -> bool {
+        match message {
+            NetMessage::Start { round, .. } => *round > self.round,
+            NetMessage::Rematch { round } => *round == self.round,
+            _ => false,
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<OnlineEvent> {
+        self.receive();
+        while let Some(message) = self.inbox.front() {
+            if self.is_pending_round_event(message) {
+                return None;
+            }
+            let message = self.inbox.pop_front()?;
+            if message.round() != self.round {
+                continue;
+            }
+            match message {
+                NetMessage::Attack { lines, .. } => return Some(OnlineEvent::Attack(lines)),
+                NetMessage::ToppedOut { .. } => return Some(OnlineEvent::OpponentToppedOut),
+                NetMessage::Meter { lines, .. } => self.opponent_incoming = lines,
+                NetMessage::Field { field, .. } => self.opponent_field = grid_from_str(&field),
+                NetMessage::Start { .. } | NetMessage::Rematch { .. } => (),
+            }
+        }
+        None
+    }
+
+    pub fn poll_round(&mut self) -> Option<RoundEvent> {
+        self.receive();
+        while let Some(message) = self.inbox.pop_front() {
+            if !self.is_pending_round_event(&message) {
+                continue;
+            }
+            match message {
+                NetMessage::Start { seed, round } => return Some(RoundEvent::Start(seed, round)),
+                NetMessage::Rematch { .. } => return Some(RoundEvent::Rematch),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    pub fn send(&mut self, message: NetMessage) {
+        let _ = self.peer.send(&message);
+    }
+
+    pub fn send_attack(&mut self, lines: usize) {
+        if lines > 0 {
+            self.send(NetMessage::Attack {
+                round: self.round,
+                lines,
+            });
+        }
+    }
+
+    pub fn send_topped_out(&mut self) {
+        self.send(NetMessage::ToppedOut { round: self.round });
+    }
+
+    pub fn send_field(&mut self, board: &Board) {
+        let field = field_of(board);
+        if field != self.last_field {
+            self.send(NetMessage::Field {
+                round: self.round,
+                field: field.clone(),
+            });
+            self.last_field = field;
+        }
+    }
+
+    pub fn send_meter(&mut self, lines: usize) {
+        if lines != self.last_meter {
+            self.send(NetMessage::Meter {
+                round: self.round,
+                lines,
+            });
+            self.last_meter = lines;
+        }
+    }
+}
+
+impl Widget for &OnlineWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [label_area, meter_area, board_area] = vertical![== 1, == 1, == ROWS - 2].areas(area);
+
+        let label = if self.peer.is_closed() {
+            line!["opponent left".red()]
+        } else {
+            line!["opponent".dim()]
+        };
+        label.centered().render(label_area, buf);
+
+        let meter = if self.opponent_incoming > 0 {
+            Line::from(span!("incoming {}", self.opponent_incoming).red().bold())
+        } else {
+            Line::from("incoming 0".dim())
+        };
+        meter.centered().render(meter_area, buf);
+
+        MiniBoardView(&self.opponent_field).render(board_area, buf);
+    }
+}
@@ -0,0 +1,17 @@
+use std::{fmt, time::Duration};
+
+pub fn write_clock(buf: &mut impl fmt::Write, duration: &Duration) -> fmt::Result {
+    let total_ms = duration.as_millis();
+
+    let minutes = (total_ms / 60_000) % 60;
+    let seconds = (total_ms / 1_000) % 60;
+    let milliseconds = total_ms % 1_000;
+
+    write!(buf, "{:02}:{:02}.{:03}", minutes, seconds, milliseconds)
+}
+
+pub fn to_clock(duration: &Duration) -> String {
+    let mut clock = String::new();
+    let _ = write_clock(&mut clock, duration);
+    clock
+}
@@ -1,3 +1,4 @@
+pub mod duration_format;
 pub mod integer_format;
-pub mod timer;
-
+#[cfg(not(feature = "vhs"))]
+pub mod storage;
@@ -0,0 +1,13 @@
+use std::{env, path::Path, path::PathBuf};
+
+pub fn data_dir() -> PathBuf {
+    if let Ok(appdata) = env::var("APPDATA") {
+        Path::new(&appdata).join("tetrus")
+    } else if let Ok(xdg_data) = env::var("XDG_DATA_HOME") {
+        Path::new(&xdg_data).join("tetrus")
+    } else if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        Path::new(&home).join(".local").join("share").join("tetrus")
+    } else {
+        PathBuf::new()
+    }
+}